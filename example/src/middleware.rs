@@ -20,10 +20,28 @@ pub async fn runtime_settings_context(request: Request<Body>, next: Next) -> Res
         })
         .collect();
 
+    let query = request
+        .uri()
+        .query()
+        .map(runtime_settings::Request::parse_query)
+        .unwrap_or_default();
+
+    // Multiple Cookie headers are joined as one cookie list
+    let cookie_header = request
+        .headers()
+        .get_all(axum::http::header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let cookies = runtime_settings::Request::parse_cookies(&cookie_header);
+
     let rs_request = runtime_settings::Request {
         method,
         path,
         headers,
+        query,
+        cookies,
     };
 
     // Execute the rest of the handler with task-local request context
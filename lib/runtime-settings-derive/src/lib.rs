@@ -0,0 +1,239 @@
+// lib/runtime-settings-derive/src/lib.rs
+//! `#[derive(RuntimeSettings)]`: typed accessors for runtime settings.
+//!
+//! Use it through the re-export in `runtime-settings`:
+//!
+//! ```ignore
+//! use runtime_settings::RuntimeSettings;
+//!
+//! #[derive(RuntimeSettings)]
+//! pub struct FeatureSettings {
+//!     /// Maximum pool size
+//!     #[setting(default = 10, validate = validate_pool_size)]
+//!     pub max_connections: u32,
+//!     #[setting(key = "FEATURE_X_ENABLED", default = false)]
+//!     pub feature_x: bool,
+//!     pub greeting: Option<String>,
+//! }
+//!
+//! let feature = FeatureSettings::accessor(settings());
+//! let max: Arc<u32> = feature.max_connections();
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, Ident, LitStr, Path,
+    PathArguments, Type, parse_macro_input,
+};
+
+/// Derive a typed accessor for runtime settings.
+///
+/// Each named field maps to a setting key. Field attributes, all optional:
+/// - `key = "KEY"`: setting key (defaults to the field name in upper case)
+/// - `default = <expr>`: value returned when the key is missing or invalid
+///   (defaults to `Default::default()`)
+/// - `validate = <path>`: `fn(&T) -> Result<(), E>` with `E: Display`; rejected
+///   values are treated as missing
+///
+/// `Option<T>` fields return `Option<Arc<T>>` and take no default.
+#[proc_macro_derive(RuntimeSettings, attributes(setting))]
+pub fn derive_runtime_settings(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct FieldSpec {
+    ident: Ident,
+    docs: Vec<Attribute>,
+    key: LitStr,
+    ty: Type,
+    optional: bool,
+    default: Option<Expr>,
+    validate: Option<Path>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "RuntimeSettings can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "RuntimeSettings can only be derived for structs",
+            ));
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "RuntimeSettings cannot be derived for generic structs",
+        ));
+    }
+
+    let specs = fields
+        .iter()
+        .map(|field| parse_field(field.ident.clone().unwrap(), field))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let accessor = format_ident!("{}Accessor", name);
+    let keys = specs.iter().map(|s| &s.key);
+    let methods = specs.iter().map(accessor_method);
+    let accessor_doc = format!("Typed accessor for the settings declared by [`{name}`]");
+
+    Ok(quote! {
+        impl #name {
+            /// Setting keys declared by this struct
+            pub const KEYS: &'static [&'static str] = &[#(#keys),*];
+
+            /// Typed accessor reading these settings from `settings`
+            pub fn accessor(
+                settings: &::runtime_settings::RuntimeSettings,
+            ) -> #accessor<'_> {
+                #accessor { settings }
+            }
+        }
+
+        #[doc = #accessor_doc]
+        #[derive(Clone, Copy)]
+        #vis struct #accessor<'a> {
+            settings: &'a ::runtime_settings::RuntimeSettings,
+        }
+
+        impl<'a> #accessor<'a> {
+            #(#methods)*
+        }
+    })
+}
+
+fn parse_field(ident: Ident, field: &syn::Field) -> syn::Result<FieldSpec> {
+    let mut key = None;
+    let mut default = None;
+    let mut validate = None;
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("setting")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") {
+                key = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse::<Expr>()?);
+            } else if meta.path.is_ident("validate") {
+                validate = Some(meta.value()?.parse::<Path>()?);
+            } else {
+                return Err(meta.error("expected `key`, `default` or `validate`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let (ty, optional) = match option_inner(&field.ty) {
+        Some(inner) => (inner.clone(), true),
+        None => (field.ty.clone(), false),
+    };
+    if optional {
+        if let Some(default) = &default {
+            return Err(syn::Error::new_spanned(
+                default,
+                "`default` is not supported on `Option` fields",
+            ));
+        }
+    }
+
+    let key = key.unwrap_or_else(|| {
+        let name = ident.to_string();
+        LitStr::new(
+            &name.trim_start_matches("r#").to_uppercase(),
+            Span::call_site(),
+        )
+    });
+    let docs = field
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .cloned()
+        .collect();
+
+    Ok(FieldSpec {
+        ident,
+        docs,
+        key,
+        ty,
+        optional,
+        default,
+        validate,
+    })
+}
+
+fn accessor_method(spec: &FieldSpec) -> TokenStream2 {
+    let FieldSpec {
+        ident,
+        docs,
+        key,
+        ty,
+        ..
+    } = spec;
+
+    let lookup = match &spec.validate {
+        Some(validate) => quote! {
+            self.settings.get_validated::<#ty, _, _>(
+                #key,
+                ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#validate)),
+                #validate,
+            )
+        },
+        None => quote! { self.settings.get::<#ty>(#key) },
+    };
+
+    if spec.optional {
+        return quote! {
+            #(#docs)*
+            pub fn #ident(&self) -> ::std::option::Option<::std::sync::Arc<#ty>> {
+                #lookup
+            }
+        };
+    }
+
+    let default = match &spec.default {
+        Some(expr) => quote! { #expr },
+        None => quote! { ::std::default::Default::default() },
+    };
+    quote! {
+        #(#docs)*
+        pub fn #ident(&self) -> ::std::sync::Arc<#ty> {
+            #lookup.unwrap_or_else(|| {
+                let default: #ty = #default;
+                ::std::sync::Arc::new(default)
+            })
+        }
+    }
+}
+
+/// `T` if `ty` is `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
@@ -0,0 +1,79 @@
+//! Benchmarks for header lookups on the `get()` hot path.
+//!
+//! Run with `cargo bench -p runtime-settings --bench header_lookup`.
+//! `legacy_scan` is the lookup used before header names were normalized
+//! and serves as the baseline.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use runtime_settings::filters::{
+    CompiledDynamicFilter, CompiledEmailFilter, CompiledHeaderFilter, CompiledHostFilter,
+};
+use runtime_settings::{CustomContext, DynamicContext, Request};
+use std::collections::HashMap;
+use std::hint::black_box;
+
+/// A request with a realistic number of mixed-case headers
+fn request() -> Request {
+    let mut headers: HashMap<String, String> = (0..20)
+        .map(|i| (format!("X-Extra-Header-{i}"), format!("value-{i}")))
+        .collect();
+    headers.insert("Host".to_string(), "api.example.com".to_string());
+    headers.insert("X-Real-Email".to_string(), "user@example.com".to_string());
+    headers.insert("X-Feature".to_string(), "enabled".to_string());
+    Request {
+        method: "GET".to_string(),
+        path: "/api/users".to_string(),
+        headers,
+        ..Default::default()
+    }
+}
+
+/// Header lookup as implemented before normalization
+fn legacy_scan<'a>(headers: &'a HashMap<String, String>, key: &str) -> Option<&'a str> {
+    let key_lower = key.to_lowercase();
+    headers
+        .iter()
+        .find(|(k, _)| k.to_lowercase() == key_lower)
+        .map(|(_, v)| v.as_str())
+}
+
+fn bench_get_header(c: &mut Criterion) {
+    let raw = request();
+    let normalized = Request::new(raw.method.clone(), raw.path.clone(), raw.headers.clone());
+
+    let mut group = c.benchmark_group("get_header");
+    group.bench_function("legacy_scan", |b| {
+        b.iter(|| legacy_scan(black_box(&raw.headers), black_box("x-real-email")))
+    });
+    group.bench_function("unnormalized", |b| {
+        b.iter(|| black_box(&raw).get_header(black_box("x-real-email")))
+    });
+    group.bench_function("normalized", |b| {
+        b.iter(|| black_box(&normalized).get_header(black_box("x-real-email")))
+    });
+    group.bench_function("normalized_miss", |b| {
+        b.iter(|| black_box(&normalized).get_header(black_box("x-missing")))
+    });
+    group.finish();
+}
+
+fn bench_filters(c: &mut Criterion) {
+    let raw = request();
+    let ctx = DynamicContext {
+        request: Some(Request::new(raw.method, raw.path, raw.headers)),
+        custom: CustomContext::new(),
+    };
+
+    let host = CompiledHostFilter::compile(".*\\.example\\.com").unwrap();
+    let email = CompiledEmailFilter::compile(".*@example\\.com").unwrap();
+    let header = CompiledHeaderFilter::compile("X-Feature=enabled,Host=api\\..*").unwrap();
+
+    let mut group = c.benchmark_group("dynamic_filters");
+    group.bench_function("host", |b| b.iter(|| host.check(black_box(&ctx))));
+    group.bench_function("email", |b| b.iter(|| email.check(black_box(&ctx))));
+    group.bench_function("header", |b| b.iter(|| header.check(black_box(&ctx))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_header, bench_filters);
+criterion_main!(benches);
@@ -3,14 +3,53 @@ use semver::Version;
 use std::collections::HashMap;
 
 /// HTTP request for context filtering
+///
+/// Header names are normalized to lowercase by `Request::new` and when the
+/// request is set into thread-local or task-local scope, so header lookups are
+/// a single hash lookup.
 #[derive(Debug, Clone, Default)]
 pub struct Request {
     pub method: String,
     pub path: String,
+    /// Headers keyed by name; lowercase once normalized
     pub headers: HashMap<String, String>,
+    /// Parsed query string parameters
+    pub query: HashMap<String, String>,
+    /// Cookies from the `Cookie` header
+    pub cookies: HashMap<String, String>,
 }
 
 impl Request {
+    /// Create a request with normalized header names
+    pub fn new(
+        method: impl Into<String>,
+        path: impl Into<String>,
+        headers: HashMap<String, String>,
+    ) -> Self {
+        let mut request = Self {
+            method: method.into(),
+            path: path.into(),
+            headers,
+            ..Default::default()
+        };
+        request.normalize_headers();
+        request
+    }
+
+    /// Lowercase header names in place. No-op if they already are.
+    pub fn normalize_headers(&mut self) {
+        let normalized = self
+            .headers
+            .keys()
+            .all(|k| !k.bytes().any(|b| b.is_ascii_uppercase()));
+        if !normalized {
+            self.headers = std::mem::take(&mut self.headers)
+                .into_iter()
+                .map(|(k, v)| (k.to_ascii_lowercase(), v))
+                .collect();
+        }
+    }
+
     /// Get host from "host" header (case-insensitive)
     pub fn host(&self) -> Option<&str> {
         self.get_header("host")
@@ -27,13 +66,59 @@ impl Request {
     }
 
     /// Get header value (case-insensitive key lookup)
+    ///
+    /// With normalized headers and a lowercase `key` this is one hash lookup.
+    /// Otherwise a miss falls back to a case-insensitive scan, which doesn't
+    /// allocate either.
     pub fn get_header(&self, key: &str) -> Option<&str> {
-        let key_lower = key.to_lowercase();
+        if let Some(value) = self.headers.get(key) {
+            return Some(value.as_str());
+        }
         self.headers
             .iter()
-            .find(|(k, _)| k.to_lowercase() == key_lower)
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
             .map(|(_, v)| v.as_str())
     }
+
+    /// Get query parameter value (case-sensitive)
+    pub fn query_param(&self, key: &str) -> Option<&str> {
+        self.query.get(key).map(|v| v.as_str())
+    }
+
+    /// Get cookie value (case-sensitive)
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies.get(name).map(|v| v.as_str())
+    }
+
+    /// Parse a query string like "a=1&b=x%20y" (without the leading `?`).
+    /// Values are percent-decoded; for repeated keys the first value wins.
+    pub fn parse_query(query: &str) -> HashMap<String, String> {
+        let mut params = HashMap::new();
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            params
+                .entry(key.into_owned())
+                .or_insert_with(|| value.into_owned());
+        }
+        params
+    }
+
+    /// Parse a `Cookie` header like "session=abc; theme=dark".
+    /// Surrounding double quotes are stripped from values.
+    pub fn parse_cookies(header: &str) -> HashMap<String, String> {
+        header
+            .split(';')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| {
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(value);
+                (name.trim().to_string(), value.to_string())
+            })
+            .filter(|(name, _)| !name.is_empty())
+            .collect()
+    }
 }
 
 /// Hierarchical custom context with eager merge (each snapshot is a complete merged state)
@@ -117,6 +202,7 @@ mod tests {
             method: "GET".to_string(),
             path: "/api".to_string(),
             headers,
+            ..Default::default()
         };
         assert_eq!(request.host(), Some("example.com"));
     }
@@ -129,6 +215,7 @@ mod tests {
             method: "GET".to_string(),
             path: "/".to_string(),
             headers,
+            ..Default::default()
         };
         assert_eq!(request.ip(), Some("192.168.1.1"));
     }
@@ -141,6 +228,7 @@ mod tests {
             method: "GET".to_string(),
             path: "/".to_string(),
             headers,
+            ..Default::default()
         };
         assert_eq!(request.email(), Some("user@example.com"));
     }
@@ -153,10 +241,61 @@ mod tests {
             method: "GET".to_string(),
             path: "/".to_string(),
             headers,
+            ..Default::default()
         };
         assert_eq!(request.ip(), Some("10.0.0.1"));
     }
 
+    #[test]
+    fn test_request_new_normalizes_headers() {
+        let headers: HashMap<String, String> = [
+            ("X-Real-IP".to_string(), "10.0.0.1".to_string()),
+            ("host".to_string(), "example.com".to_string()),
+        ]
+        .into();
+        let request = Request::new("GET", "/", headers);
+        assert!(request.headers.contains_key("x-real-ip"));
+        assert_eq!(request.get_header("X-REAL-IP"), Some("10.0.0.1"));
+        assert_eq!(request.host(), Some("example.com"));
+        assert_eq!(request.get_header("missing"), None);
+    }
+
+    #[test]
+    fn test_get_header_finds_keys_inserted_after_new() {
+        let mut request = Request::new("GET", "/", HashMap::new());
+        request
+            .headers
+            .insert("X-Late".to_string(), "1".to_string());
+        assert_eq!(request.get_header("x-late"), Some("1"));
+        assert_eq!(request.get_header("X-LATE"), Some("1"));
+    }
+
+    #[test]
+    fn test_request_parse_query() {
+        let query = Request::parse_query("utm_source=news%20letter&a=1&a=2&flag&q=x+y");
+        assert_eq!(
+            query.get("utm_source").map(String::as_str),
+            Some("news letter")
+        );
+        assert_eq!(query.get("a").map(String::as_str), Some("1"));
+        assert_eq!(query.get("flag").map(String::as_str), Some(""));
+        assert_eq!(query.get("q").map(String::as_str), Some("x y"));
+        assert!(Request::parse_query("").is_empty());
+    }
+
+    #[test]
+    fn test_request_parse_cookies() {
+        let cookies = Request::parse_cookies("session=abc; theme=\"dark\";broken; =x");
+        assert_eq!(cookies.len(), 2);
+        let request = Request {
+            cookies,
+            ..Default::default()
+        };
+        assert_eq!(request.cookie("session"), Some("abc"));
+        assert_eq!(request.cookie("theme"), Some("dark"));
+        assert_eq!(request.cookie("Session"), None);
+    }
+
     #[test]
     fn test_custom_context_single_layer() {
         let mut ctx = CustomContext::new();
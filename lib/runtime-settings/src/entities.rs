@@ -2,37 +2,69 @@
 use crate::context::{DynamicContext, StaticContext};
 use crate::error::SettingsError;
 use crate::filters::{
-    compile_dynamic_filter, compile_static_filter, is_static_filter, CompiledDynamicFilter,
-    CompiledStaticFilter,
+    CompiledComposite, CompiledDynamicFilter, CompiledStaticFilter, FilterRegistry,
+    compile_composite_filter, compile_dynamic_filter_for_key, compile_static_filter,
+    is_composite_filter, is_dynamic_filter, is_static_filter,
+};
+use crate::secrets::{
+    SecretUsage, SecretsService, find_secret_usages, redact_secrets, resolve_secrets_sync,
 };
-use crate::secrets::{find_secret_usages, resolve_secrets_sync, SecretUsage, SecretsService};
 use dashmap::DashMap;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Raw setting as deserialized from JSON
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawSetting {
     pub key: String,
     pub priority: i64,
-    #[serde(default)]
+    /// Filter name to pattern. Composite filters (`any`, `all`, `not`) may be
+    /// given as nested JSON and are kept here as JSON text.
+    #[serde(default, deserialize_with = "deserialize_filter")]
     pub filter: HashMap<String, String>,
     pub value: serde_json::Value,
 }
 
+/// Deserialize a filter map, keeping non-string values as JSON text
+pub(crate) fn deserialize_filter<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = HashMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    Ok(raw
+        .into_iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(pattern) => (name, pattern),
+            other => (name, other.to_string()),
+        })
+        .collect())
+}
+
 /// One setting with compiled filters for efficient matching
 pub struct Setting {
     pub key: String,
     pub priority: i64,
     pub value: serde_json::Value,
+    /// Raw filters the setting was compiled from
+    pub filter: HashMap<String, String>,
+    /// Name of the provider the setting was loaded from (empty if unknown)
+    pub provider: String,
+    /// Provider version the setting was loaded at (empty if the provider is unversioned)
+    pub provider_version: String,
     pub static_filters: Vec<Box<dyn CompiledStaticFilter>>,
     pub dynamic_filters: Vec<Box<dyn CompiledDynamicFilter>>,
+    /// Filter name to its position in `static_filters` or `dynamic_filters`
+    filter_slots: HashMap<String, FilterSlot>,
     /// Cache of deserialized values by TypeId
     value_cache: DashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    /// Validator verdicts by value TypeId and validator id, cleared with `value_cache`
+    validation_cache: DashMap<(TypeId, &'static str), bool>,
     /// Secret references found in the value
     secrets_usages: Vec<SecretUsage>,
     /// Version of secrets when cache was populated
@@ -44,7 +76,9 @@ impl std::fmt::Debug for Setting {
         f.debug_struct("Setting")
             .field("key", &self.key)
             .field("priority", &self.priority)
-            .field("value", &self.value)
+            .field("provider", &self.provider)
+            .field("provider_version", &self.provider_version)
+            .field("value", &self.redacted_value())
             .field("static_filters_count", &self.static_filters.len())
             .field("dynamic_filters_count", &self.dynamic_filters.len())
             .field("cached_types_count", &self.value_cache.len())
@@ -53,22 +87,54 @@ impl std::fmt::Debug for Setting {
     }
 }
 
+/// Position of a compiled filter in `Setting`
+#[derive(Debug, Clone, Copy)]
+enum FilterSlot {
+    Static(usize),
+    Dynamic(usize),
+}
+
+/// A compiled filter of a setting, looked up by name
+pub(crate) enum CompiledFilterRef<'a> {
+    Static(&'a dyn CompiledStaticFilter),
+    Dynamic(&'a dyn CompiledDynamicFilter),
+}
+
 impl Setting {
-    /// Compile a RawSetting into a Setting with pre-compiled filters
-    pub fn compile(raw: RawSetting) -> Result<Self, SettingsError> {
+    /// Compile a RawSetting into a Setting with pre-compiled filters.
+    ///
+    /// Filters that aren't built in are looked up in `registry`.
+    pub fn compile(raw: RawSetting, registry: &FilterRegistry) -> Result<Self, SettingsError> {
         let mut static_filters: Vec<Box<dyn CompiledStaticFilter>> = Vec::new();
         let mut dynamic_filters: Vec<Box<dyn CompiledDynamicFilter>> = Vec::new();
+        let mut filter_slots = HashMap::new();
 
         for (name, pattern) in &raw.filter {
-            if is_static_filter(name) {
-                static_filters.push(compile_static_filter(name, pattern)?);
-            } else {
-                // Try to compile as dynamic filter, ignore unknown filters
-                if let Ok(filter) = compile_dynamic_filter(name, pattern) {
-                    dynamic_filters.push(filter);
+            let slot = if is_composite_filter(name) {
+                match compile_composite_filter(&raw.key, name, pattern, registry)? {
+                    CompiledComposite::Static(filter) => {
+                        static_filters.push(filter);
+                        FilterSlot::Static(static_filters.len() - 1)
+                    }
+                    CompiledComposite::Dynamic(filter) => {
+                        dynamic_filters.push(filter);
+                        FilterSlot::Dynamic(dynamic_filters.len() - 1)
+                    }
                 }
+            } else if is_static_filter(name, registry) {
+                static_filters.push(compile_static_filter(name, pattern, registry)?);
+                FilterSlot::Static(static_filters.len() - 1)
+            } else if is_dynamic_filter(name, registry) {
+                // A known filter with a bad pattern fails closed
+                dynamic_filters.push(compile_dynamic_filter_for_key(
+                    &raw.key, name, pattern, registry,
+                )?);
+                FilterSlot::Dynamic(dynamic_filters.len() - 1)
+            } else {
                 // Unknown filters are silently ignored for backwards compatibility
-            }
+                continue;
+            };
+            filter_slots.insert(name.clone(), slot);
         }
 
         // Parse secret references from value
@@ -78,9 +144,14 @@ impl Setting {
             key: raw.key,
             priority: raw.priority,
             value: raw.value,
+            filter: raw.filter,
+            provider: String::new(),
+            provider_version: String::new(),
             static_filters,
             dynamic_filters,
+            filter_slots,
             value_cache: DashMap::new(),
+            validation_cache: DashMap::new(),
             secrets_usages,
             cached_at_version: AtomicU64::new(0),
         })
@@ -97,7 +168,9 @@ impl Setting {
         let cached = self.cached_at_version.load(Ordering::Acquire);
         if cached != secrets_version {
             self.value_cache.clear();
-            self.cached_at_version.store(secrets_version, Ordering::Release);
+            self.validation_cache.clear();
+            self.cached_at_version
+                .store(secrets_version, Ordering::Release);
         }
     }
 
@@ -106,6 +179,11 @@ impl Setting {
         &self.secrets_usages
     }
 
+    /// The value with every secret usage replaced by `[REDACTED]`, for logs
+    pub fn redacted_value(&self) -> serde_json::Value {
+        redact_secrets(&self.value, &self.secrets_usages)
+    }
+
     /// Check all static filters against the given context
     pub fn check_static_filters(&self, ctx: &StaticContext) -> bool {
         self.static_filters.iter().all(|f| f.check(ctx))
@@ -116,6 +194,18 @@ impl Setting {
         self.dynamic_filters.iter().all(|f| f.check(ctx))
     }
 
+    /// The compiled filter for `name`, `None` for ignored filters
+    pub(crate) fn compiled_filter(&self, name: &str) -> Option<CompiledFilterRef<'_>> {
+        match *self.filter_slots.get(name)? {
+            FilterSlot::Static(i) => Some(CompiledFilterRef::Static(
+                self.static_filters.get(i)?.as_ref(),
+            )),
+            FilterSlot::Dynamic(i) => Some(CompiledFilterRef::Dynamic(
+                self.dynamic_filters.get(i)?.as_ref(),
+            )),
+        }
+    }
+
     /// Get the setting value with caching by TypeId.
     ///
     /// If the setting contains secret references, resolves them synchronously
@@ -172,6 +262,43 @@ impl Setting {
 
         Some(arc_value)
     }
+
+    /// Get the value if `validate` accepts it.
+    ///
+    /// The verdict is cached like the value under `validator`, so each validator
+    /// runs (and logs a rejection) once per setting version rather than on every
+    /// call. Validators that can disagree need distinct ids.
+    pub fn get_validated_value<T, F, E>(
+        &self,
+        secrets: &SecretsService,
+        validator: &'static str,
+        validate: F,
+    ) -> Option<Arc<T>>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce(&T) -> Result<(), E>,
+        E: std::fmt::Display,
+    {
+        let value = self.get_value::<T>(secrets)?;
+        let cache_key = (TypeId::of::<T>(), validator);
+
+        let valid = match self.validation_cache.get(&cache_key) {
+            Some(valid) => *valid,
+            None => {
+                let valid = match validate(&value) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::warn!(key = %self.key, validator, error = %e, "Setting value rejected by validator");
+                        false
+                    }
+                };
+                self.validation_cache.insert(cache_key, valid);
+                valid
+            }
+        };
+
+        valid.then_some(value)
+    }
 }
 
 /// Identifier for deleting a setting
@@ -182,7 +309,7 @@ pub struct SettingKey {
 }
 
 /// Response from MCS
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McsResponse {
     pub settings: Vec<RawSetting>,
     #[serde(default)]
@@ -193,6 +320,7 @@ pub struct McsResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filters::CustomFilter;
 
     #[test]
     fn test_raw_setting_deserialize() {
@@ -219,6 +347,28 @@ mod tests {
         assert_eq!(setting.value, serde_json::json!(123));
     }
 
+    #[test]
+    fn test_raw_setting_deserialize_composite_filter() {
+        let json = r#"{
+            "key": "KEY",
+            "priority": 0,
+            "filter": {
+                "application": "my-app",
+                "any": [{"host": "a"}, {"email": "b"}],
+                "not": {"url-path": "/internal"}
+            },
+            "value": 1
+        }"#;
+        let setting: RawSetting = serde_json::from_str(json).unwrap();
+        assert_eq!(setting.filter["application"], "my-app");
+        assert_eq!(setting.filter["not"], r#"{"url-path":"/internal"}"#);
+
+        // Round-trips through serialization (e.g. the MCS cache)
+        let json = serde_json::to_string(&setting).unwrap();
+        let again: RawSetting = serde_json::from_str(&json).unwrap();
+        assert_eq!(again.filter, setting.filter);
+    }
+
     #[test]
     fn test_setting_key_deserialize() {
         let json = r#"{"key": "KEY", "priority": -1000000000000000000}"#;
@@ -235,7 +385,7 @@ mod tests {
             filter: HashMap::new(),
             value: serde_json::json!("value"),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
         assert_eq!(setting.key, "KEY");
         assert_eq!(setting.priority, 100);
         assert!(setting.static_filters.is_empty());
@@ -250,7 +400,7 @@ mod tests {
             filter: [("application".to_string(), "my-app".to_string())].into(),
             value: serde_json::json!("value"),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
         assert_eq!(setting.static_filters.len(), 1);
         assert!(setting.dynamic_filters.is_empty());
     }
@@ -263,7 +413,7 @@ mod tests {
             filter: [("url-path".to_string(), "/api/.*".to_string())].into(),
             value: serde_json::json!("value"),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
         assert!(setting.static_filters.is_empty());
         assert_eq!(setting.dynamic_filters.len(), 1);
     }
@@ -282,7 +432,7 @@ mod tests {
             .into(),
             value: serde_json::json!("value"),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
         assert_eq!(setting.static_filters.len(), 2);
         assert_eq!(setting.dynamic_filters.len(), 2);
     }
@@ -295,11 +445,64 @@ mod tests {
             filter: [("unknown_filter".to_string(), "value".to_string())].into(),
             value: serde_json::json!("value"),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
         assert!(setting.static_filters.is_empty());
         assert!(setting.dynamic_filters.is_empty());
     }
 
+    #[test]
+    fn test_setting_compile_rejects_custom_filter_bad_pattern() {
+        let mut registry = FilterRegistry::new();
+        registry
+            .register(
+                "tenant",
+                CustomFilter::Dynamic(Arc::new(|pattern: &str| {
+                    Err(SettingsError::InvalidRegex {
+                        pattern: pattern.to_string(),
+                        error: "rejected".to_string(),
+                    })
+                })),
+            )
+            .unwrap();
+        let raw = RawSetting {
+            key: "KEY".to_string(),
+            priority: 100,
+            filter: [("tenant".to_string(), "bad".to_string())].into(),
+            value: serde_json::json!("value"),
+        };
+        // A registered filter that rejects its pattern fails closed
+        assert!(Setting::compile(raw, &registry).is_err());
+    }
+
+    #[test]
+    fn test_setting_compile_with_composite_filters() {
+        let raw: RawSetting = serde_json::from_value(serde_json::json!({
+            "key": "KEY",
+            "priority": 100,
+            "filter": {
+                "any": [{"application": "app-a"}, {"application": "app-b"}],
+                "not": {"url-path": "/internal/.*"}
+            },
+            "value": "value"
+        }))
+        .unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
+        assert_eq!(setting.static_filters.len(), 1);
+        assert_eq!(setting.dynamic_filters.len(), 1);
+    }
+
+    #[test]
+    fn test_setting_compile_rejects_invalid_composite() {
+        let raw = RawSetting {
+            key: "KEY".to_string(),
+            priority: 100,
+            filter: [("not".to_string(), r#"{"unknown": "x"}"#.to_string())].into(),
+            value: serde_json::json!("value"),
+        };
+        // Unknown filters inside a composite fail instead of being ignored
+        assert!(Setting::compile(raw, &FilterRegistry::new()).is_err());
+    }
+
     #[test]
     fn test_setting_check_static_filters() {
         let raw = RawSetting {
@@ -308,7 +511,7 @@ mod tests {
             filter: [("application".to_string(), "my-app".to_string())].into(),
             value: serde_json::json!("value"),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
 
         let ctx_match = StaticContext {
             application: "my-app".to_string(),
@@ -337,13 +540,14 @@ mod tests {
             filter: [("url-path".to_string(), "/api/.*".to_string())].into(),
             value: serde_json::json!("value"),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
 
         let ctx_match = DynamicContext {
             request: Some(crate::context::Request {
                 method: "GET".to_string(),
                 path: "/api/users".to_string(),
                 headers: HashMap::new(),
+                ..Default::default()
             }),
             custom: Default::default(),
         };
@@ -354,6 +558,7 @@ mod tests {
                 method: "GET".to_string(),
                 path: "/other/path".to_string(),
                 headers: HashMap::new(),
+                ..Default::default()
             }),
             custom: Default::default(),
         };
@@ -368,7 +573,7 @@ mod tests {
             filter: HashMap::new(),
             value: serde_json::json!({"host": "localhost", "port": 5432}),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
         assert!(!setting.has_secrets());
     }
 
@@ -383,7 +588,7 @@ mod tests {
                 "password": {"$secret": "secret/data/db/creds:password"}
             }),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
         assert!(setting.has_secrets());
         assert_eq!(setting.secrets_usages().len(), 1);
     }
@@ -398,13 +603,70 @@ mod tests {
                 "password": {"$secret": "invalid-no-colon"}
             }),
         };
-        let result = Setting::compile(raw);
+        let result = Setting::compile(raw, &FilterRegistry::new());
         assert!(matches!(
             result,
             Err(crate::error::SettingsError::InvalidSecretReference { .. })
         ));
     }
 
+    #[test]
+    fn test_setting_compile_secret_template() {
+        let raw = RawSetting {
+            key: "KEY".to_string(),
+            priority: 100,
+            filter: HashMap::new(),
+            value: serde_json::json!("postgres://app:${secret:secret/data/db:password}@db/app"),
+        };
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
+        assert!(setting.has_secrets());
+        assert!(matches!(
+            setting.secrets_usages(),
+            [SecretUsage::Template { .. }]
+        ));
+
+        let raw = RawSetting {
+            key: "KEY".to_string(),
+            priority: 100,
+            filter: HashMap::new(),
+            value: serde_json::json!("postgres://app:${secret:no-colon}@db/app"),
+        };
+        assert!(matches!(
+            Setting::compile(raw, &FilterRegistry::new()),
+            Err(crate::error::SettingsError::InvalidSecretReference { .. })
+        ));
+
+        // Only escaped placeholders: a plain value without secrets
+        let raw = RawSetting {
+            key: "KEY".to_string(),
+            priority: 100,
+            filter: HashMap::new(),
+            value: serde_json::json!("$${secret:a:b}"),
+        };
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
+        assert!(!setting.has_secrets());
+        assert!(setting.secrets_usages().is_empty());
+    }
+
+    #[test]
+    fn test_setting_debug_redacts_secrets() {
+        let raw = RawSetting {
+            key: "KEY".to_string(),
+            priority: 100,
+            filter: HashMap::new(),
+            value: serde_json::json!({
+                "host": "localhost",
+                "dsn": "postgres://app:${secret:secret/data/db:password}@db"
+            }),
+        };
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
+
+        let debug = format!("{:?}", setting);
+        assert!(debug.contains("localhost"));
+        assert!(debug.contains("[REDACTED]"));
+        assert!(!debug.contains("secret/data/db"));
+    }
+
     #[test]
     fn test_setting_invalidate_if_stale() {
         let raw = RawSetting {
@@ -415,26 +677,32 @@ mod tests {
                 "password": {"$secret": "secret/data/db:pass"}
             }),
         };
-        let setting = Setting::compile(raw).unwrap();
+        let setting = Setting::compile(raw, &FilterRegistry::new()).unwrap();
 
         // First call with version 1
         setting.invalidate_if_stale(1);
         assert_eq!(
-            setting.cached_at_version.load(std::sync::atomic::Ordering::Acquire),
+            setting
+                .cached_at_version
+                .load(std::sync::atomic::Ordering::Acquire),
             1
         );
 
         // Same version - no change
         setting.invalidate_if_stale(1);
         assert_eq!(
-            setting.cached_at_version.load(std::sync::atomic::Ordering::Acquire),
+            setting
+                .cached_at_version
+                .load(std::sync::atomic::Ordering::Acquire),
             1
         );
 
         // Different version - updates
         setting.invalidate_if_stale(2);
         assert_eq!(
-            setting.cached_at_version.load(std::sync::atomic::Ordering::Acquire),
+            setting
+                .cached_at_version
+                .load(std::sync::atomic::Ordering::Acquire),
             2
         );
     }
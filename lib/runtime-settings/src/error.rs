@@ -1,4 +1,5 @@
 // lib/runtime-settings/src/error.rs
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -39,9 +40,30 @@ pub enum SettingsError {
     #[error("Invalid version specifier: {spec}")]
     InvalidVersionSpec { spec: String },
 
+    #[error("Invalid filter expression: {pattern}, error: {error}")]
+    InvalidFilterExpr { pattern: String, error: String },
+
+    #[error("Invalid IP range: {pattern}, error: {error}")]
+    InvalidIpRange { pattern: String, error: String },
+
+    #[error("Filter name is reserved by a built-in filter: {name}")]
+    ReservedFilterName { name: String },
+
+    #[error("Invalid schedule: {pattern}, error: {error}")]
+    InvalidSchedule { pattern: String, error: String },
+
+    #[error("Invalid JSON Schema for {key}: {error}")]
+    InvalidSchema { key: String, error: String },
+
     #[error("Request timed out")]
     Timeout,
 
+    #[error("Watcher for {key} timed out after {timeout:?}")]
+    WatcherTimeout { key: String, timeout: Duration },
+
+    #[error("Watcher for {key} panicked: {message}")]
+    WatcherPanicked { key: String, message: String },
+
     #[error("Missing required configuration: {0}")]
     MissingConfig(String),
 }
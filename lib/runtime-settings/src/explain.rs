@@ -0,0 +1,339 @@
+// lib/runtime-settings/src/explain.rs
+//! Structured trace of how `RuntimeSettings::get()` resolves a key.
+
+use crate::context::{DynamicContext, StaticContext};
+use crate::entities::{CompiledFilterRef, Setting};
+use serde::Serialize;
+
+/// Why `get(key)` returns what it returns
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    pub key: String,
+    /// All loaded candidates for the key, highest priority first
+    pub candidates: Vec<CandidateTrace>,
+    /// Index into `candidates` of the setting `get()` returns, if any
+    pub winner: Option<usize>,
+}
+
+impl Explanation {
+    /// The candidate `get()` returns, if any
+    pub fn winning_candidate(&self) -> Option<&CandidateTrace> {
+        self.winner.map(|i| &self.candidates[i])
+    }
+}
+
+/// One candidate setting and how its filters evaluated
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateTrace {
+    pub priority: i64,
+    /// Provider the setting was loaded from ("env", "file", "mcs", ...)
+    pub provider: String,
+    /// Provider version the setting was loaded at
+    pub provider_version: String,
+    /// Setting value, with secret references replaced by `[REDACTED]`
+    pub value: serde_json::Value,
+    pub filters: Vec<FilterTrace>,
+    /// True if every filter passed
+    pub matched: bool,
+}
+
+/// Result of a single filter
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterTrace {
+    pub name: String,
+    pub kind: FilterKind,
+    pub pattern: String,
+    /// Value the filter was checked against, when the filter reads a single input
+    pub input: Option<String>,
+    pub outcome: FilterOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterKind {
+    Static,
+    Dynamic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOutcome {
+    Match,
+    NoMatch,
+    /// Unknown filter, ignored during compilation
+    Ignored,
+}
+
+/// Build the trace for one key from its priority-sorted candidates.
+///
+/// Filters are evaluated through each setting's compiled filters, the same
+/// ones `get()` uses.
+pub(crate) fn explain(
+    key: &str,
+    settings: &[impl AsRef<Setting>],
+    static_ctx: &StaticContext,
+    ctx: &DynamicContext,
+) -> Explanation {
+    let candidates: Vec<CandidateTrace> = settings
+        .iter()
+        .map(|s| trace_candidate(s.as_ref(), static_ctx, ctx))
+        .collect();
+    let winner = candidates.iter().position(|c| c.matched);
+
+    Explanation {
+        key: key.to_string(),
+        candidates,
+        winner,
+    }
+}
+
+fn trace_candidate(
+    setting: &Setting,
+    static_ctx: &StaticContext,
+    ctx: &DynamicContext,
+) -> CandidateTrace {
+    let mut filters: Vec<FilterTrace> = setting
+        .filter
+        .iter()
+        .map(|(name, pattern)| trace_filter(setting, name, pattern, static_ctx, ctx))
+        .collect();
+    filters.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let matched = filters.iter().all(|f| f.outcome != FilterOutcome::NoMatch);
+
+    CandidateTrace {
+        priority: setting.priority,
+        provider: setting.provider.clone(),
+        provider_version: setting.provider_version.clone(),
+        value: setting.redacted_value(),
+        filters,
+        matched,
+    }
+}
+
+fn trace_filter(
+    setting: &Setting,
+    name: &str,
+    pattern: &str,
+    static_ctx: &StaticContext,
+    ctx: &DynamicContext,
+) -> FilterTrace {
+    let (kind, input, outcome) = match setting.compiled_filter(name) {
+        Some(CompiledFilterRef::Static(f)) => (
+            FilterKind::Static,
+            static_input(name, static_ctx),
+            outcome_of(f.check(static_ctx)),
+        ),
+        Some(CompiledFilterRef::Dynamic(f)) => {
+            (FilterKind::Dynamic, f.input(ctx), outcome_of(f.check(ctx)))
+        }
+        None => (FilterKind::Dynamic, None, FilterOutcome::Ignored),
+    };
+
+    FilterTrace {
+        name: name.to_string(),
+        kind,
+        pattern: pattern.to_string(),
+        input,
+        outcome,
+    }
+}
+
+fn outcome_of(matched: bool) -> FilterOutcome {
+    if matched {
+        FilterOutcome::Match
+    } else {
+        FilterOutcome::NoMatch
+    }
+}
+
+fn static_input(name: &str, ctx: &StaticContext) -> Option<String> {
+    match name {
+        "application" => Some(ctx.application.clone()),
+        "server" => Some(ctx.server.clone()),
+        "mcs_run_env" => ctx.mcs_run_env.clone(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Request;
+    use crate::entities::RawSetting;
+    use crate::filters::FilterRegistry;
+    use std::collections::HashMap;
+
+    fn static_ctx() -> StaticContext {
+        StaticContext {
+            application: "my-app".to_string(),
+            server: "server-1".to_string(),
+            environment: HashMap::new(),
+            libraries_versions: HashMap::new(),
+            mcs_run_env: None,
+        }
+    }
+
+    fn setting(priority: i64, filter: &[(&str, &str)], value: serde_json::Value) -> Setting {
+        Setting::compile(
+            RawSetting {
+                key: "KEY".to_string(),
+                priority,
+                filter: filter
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                value,
+            },
+            &FilterRegistry::new(),
+        )
+        .unwrap()
+    }
+
+    fn request_ctx(path: &str) -> DynamicContext {
+        DynamicContext {
+            request: Some(Request {
+                method: "GET".to_string(),
+                path: path.to_string(),
+                headers: HashMap::new(),
+                ..Default::default()
+            }),
+            custom: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_explain_picks_first_matching_candidate() {
+        let settings = vec![
+            Box::new(setting(
+                100,
+                &[("url-path", "/admin/.*")],
+                serde_json::json!("admin"),
+            )),
+            Box::new(setting(10, &[], serde_json::json!("default"))),
+        ];
+
+        let explanation = explain("KEY", &settings, &static_ctx(), &request_ctx("/api/users"));
+
+        assert_eq!(explanation.candidates.len(), 2);
+        assert_eq!(explanation.winner, Some(1));
+        assert_eq!(
+            explanation.winning_candidate().unwrap().value,
+            serde_json::json!("default")
+        );
+
+        let rejected = &explanation.candidates[0];
+        assert!(!rejected.matched);
+        assert_eq!(rejected.filters[0].name, "url-path");
+        assert_eq!(rejected.filters[0].kind, FilterKind::Dynamic);
+        assert_eq!(rejected.filters[0].input.as_deref(), Some("/api/users"));
+        assert_eq!(rejected.filters[0].outcome, FilterOutcome::NoMatch);
+    }
+
+    #[test]
+    fn test_explain_static_and_unknown_filters() {
+        let settings = vec![Box::new(setting(
+            100,
+            &[("application", "my-.*"), ("unknown_filter", "x")],
+            serde_json::json!(1),
+        ))];
+
+        let explanation = explain("KEY", &settings, &static_ctx(), &DynamicContext::default());
+        let filters = &explanation.candidates[0].filters;
+
+        assert_eq!(filters[0].name, "application");
+        assert_eq!(filters[0].kind, FilterKind::Static);
+        assert_eq!(filters[0].input.as_deref(), Some("my-app"));
+        assert_eq!(filters[0].outcome, FilterOutcome::Match);
+
+        assert_eq!(filters[1].name, "unknown_filter");
+        assert_eq!(filters[1].outcome, FilterOutcome::Ignored);
+
+        assert_eq!(explanation.winner, Some(0));
+    }
+
+    #[test]
+    fn test_explain_reports_inputs_of_dynamic_filters() {
+        let settings = vec![Box::new(setting(
+            100,
+            &[
+                ("header", "X-Tenant=acme"),
+                ("context", "region=eu"),
+                ("query", "ref=.*"),
+                ("cookie", "beta=1"),
+                ("rollout", "header:x-user-id=50"),
+            ],
+            serde_json::json!(1),
+        ))];
+
+        let mut request = Request::new(
+            "GET",
+            "/",
+            [
+                ("X-Tenant".to_string(), "acme".to_string()),
+                ("X-User-Id".to_string(), "42".to_string()),
+            ]
+            .into(),
+        );
+        request.query = [("ref".to_string(), "mail".to_string())].into();
+        request.cookies = [("beta".to_string(), "1".to_string())].into();
+        let mut custom = crate::context::CustomContext::new();
+        custom.push_layer([("region".to_string(), "eu".to_string())].into());
+        let ctx = DynamicContext {
+            request: Some(request),
+            custom,
+        };
+
+        let explanation = explain("KEY", &settings, &static_ctx(), &ctx);
+        let inputs: Vec<(&str, Option<&str>)> = explanation.candidates[0]
+            .filters
+            .iter()
+            .map(|f| (f.name.as_str(), f.input.as_deref()))
+            .collect();
+        assert_eq!(
+            inputs,
+            [
+                ("context", Some("region=eu")),
+                ("cookie", Some("beta=1")),
+                ("header", Some("x-tenant=acme")),
+                ("query", Some("ref=mail")),
+                ("rollout", Some("42")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explain_redacts_secrets() {
+        let settings = vec![Box::new(setting(
+            100,
+            &[],
+            serde_json::json!({
+                "host": "db",
+                "password": {"$secret": "secret/data/db:password"}
+            }),
+        ))];
+
+        let explanation = explain("KEY", &settings, &static_ctx(), &DynamicContext::default());
+        assert_eq!(
+            explanation.candidates[0].value,
+            serde_json::json!({"host": "db", "password": "[REDACTED]"})
+        );
+    }
+
+    #[test]
+    fn test_explain_serializes_to_json() {
+        let settings = vec![Box::new(setting(
+            100,
+            &[("url-path", "/api/.*")],
+            serde_json::json!("value"),
+        ))];
+
+        let explanation = explain("KEY", &settings, &static_ctx(), &request_ctx("/api/x"));
+        let json = serde_json::to_value(&explanation).unwrap();
+
+        assert_eq!(json["key"], "KEY");
+        assert_eq!(json["winner"], 0);
+        assert_eq!(json["candidates"][0]["filters"][0]["kind"], "dynamic");
+        assert_eq!(json["candidates"][0]["filters"][0]["outcome"], "match");
+    }
+}
@@ -0,0 +1,359 @@
+// lib/runtime-settings/src/filters/composite.rs
+//! Boolean composition of filters: `any`, `all` and `not`.
+//!
+//! In settings JSON a composite is a filter entry whose value is a nested
+//! structure instead of a pattern string:
+//!
+//! ```json5
+//! "filter": {
+//!   "application": "my-app",
+//!   "any": [{"host": "a\\.example\\.com"}, {"email": ".*@b\\.com"}],
+//!   "not": {"url-path": "/internal/.*"}
+//! }
+//! ```
+//!
+//! Each object is the AND of its entries and may nest further composites.
+//! The flat filter map keeps composites as their JSON text, so providers that
+//! only carry string values can send them as strings too.
+
+use super::{
+    CompiledDynamicFilter, CompiledStaticFilter, FilterRegistry, compile_dynamic_filter_for_key,
+    compile_static_filter, is_static_filter,
+};
+use crate::context::{DynamicContext, StaticContext};
+use crate::error::SettingsError;
+
+/// Filter names that hold a composite expression
+const COMPOSITE_FILTER_NAMES: &[&str] = &["any", "all", "not"];
+
+/// Check if a filter name is a composite (`any`, `all`, `not`)
+pub fn is_composite_filter(name: &str) -> bool {
+    COMPOSITE_FILTER_NAMES.contains(&name)
+}
+
+/// Parsed filter expression tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterExpr {
+    Leaf { name: String, pattern: String },
+    All(Vec<FilterExpr>),
+    Any(Vec<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+/// A composite compiled into the static or the dynamic filter family
+pub enum CompiledComposite {
+    Static(Box<dyn CompiledStaticFilter>),
+    Dynamic(Box<dyn CompiledDynamicFilter>),
+}
+
+impl FilterExpr {
+    /// Parse a composite filter entry, e.g. name `any` with pattern
+    /// `[{"host": "a"}, {"email": "b"}]`
+    pub fn parse(name: &str, pattern: &str) -> Result<Self, SettingsError> {
+        let value: serde_json::Value =
+            serde_json::from_str(pattern).map_err(|e| invalid(pattern, e.to_string()))?;
+        Self::from_entry(name, &value).map_err(|error| invalid(pattern, error))
+    }
+
+    fn from_entry(name: &str, value: &serde_json::Value) -> Result<Self, String> {
+        match name {
+            "any" | "all" => {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| format!("`{}` expects an array of filter objects", name))?;
+                let exprs = items
+                    .iter()
+                    .map(Self::from_object)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(if name == "any" {
+                    Self::Any(exprs)
+                } else {
+                    Self::All(exprs)
+                })
+            }
+            "not" => Ok(Self::Not(Box::new(Self::from_object(value)?))),
+            _ => match value {
+                serde_json::Value::String(pattern) => Ok(Self::Leaf {
+                    name: name.to_string(),
+                    pattern: pattern.clone(),
+                }),
+                other => Err(format!("filter `{}` expects a string, got {}", name, other)),
+            },
+        }
+    }
+
+    /// An object is the AND of its entries
+    fn from_object(value: &serde_json::Value) -> Result<Self, String> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| format!("expected a filter object, got {}", value))?;
+        let mut exprs = object
+            .iter()
+            .map(|(name, value)| Self::from_entry(name, value))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Self::All(exprs)
+        })
+    }
+
+    /// `Some(true)` if every leaf is a static filter, `Some(false)` if every
+    /// leaf is dynamic, `None` if the tree mixes both or has no leaves
+    fn is_static(&self, registry: &FilterRegistry) -> Option<bool> {
+        match self {
+            Self::Leaf { name, .. } => Some(is_static_filter(name, registry)),
+            Self::Not(inner) => inner.is_static(registry),
+            Self::All(exprs) | Self::Any(exprs) => {
+                let mut kinds = exprs.iter().map(|e| e.is_static(registry));
+                let first = kinds.next()??;
+                kinds.all(|k| k == Some(first)).then_some(first)
+            }
+        }
+    }
+
+    fn compile_static(
+        &self,
+        registry: &FilterRegistry,
+    ) -> Result<CompositeNode<Box<dyn CompiledStaticFilter>>, SettingsError> {
+        Ok(match self {
+            Self::Leaf { name, pattern } => {
+                CompositeNode::Leaf(compile_static_filter(name, pattern, registry)?)
+            }
+            Self::All(exprs) => CompositeNode::All(
+                exprs
+                    .iter()
+                    .map(|e| e.compile_static(registry))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Self::Any(exprs) => CompositeNode::Any(
+                exprs
+                    .iter()
+                    .map(|e| e.compile_static(registry))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Self::Not(inner) => CompositeNode::Not(Box::new(inner.compile_static(registry)?)),
+        })
+    }
+
+    fn compile_dynamic(
+        &self,
+        key: &str,
+        registry: &FilterRegistry,
+    ) -> Result<CompositeNode<Box<dyn CompiledDynamicFilter>>, SettingsError> {
+        Ok(match self {
+            Self::Leaf { name, pattern } => CompositeNode::Leaf(compile_dynamic_filter_for_key(
+                key, name, pattern, registry,
+            )?),
+            Self::All(exprs) => CompositeNode::All(
+                exprs
+                    .iter()
+                    .map(|e| e.compile_dynamic(key, registry))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Self::Any(exprs) => CompositeNode::Any(
+                exprs
+                    .iter()
+                    .map(|e| e.compile_dynamic(key, registry))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Self::Not(inner) => CompositeNode::Not(Box::new(inner.compile_dynamic(key, registry)?)),
+        })
+    }
+}
+
+/// Parse and compile a composite filter entry for a setting key.
+///
+/// Unlike top-level filters, unknown filters inside a composite are an error:
+/// silently dropping a leaf under `not` would invert its meaning.
+pub fn compile_composite_filter(
+    key: &str,
+    name: &str,
+    pattern: &str,
+    registry: &FilterRegistry,
+) -> Result<CompiledComposite, SettingsError> {
+    let expr = FilterExpr::parse(name, pattern)?;
+    match expr.is_static(registry) {
+        Some(true) => Ok(CompiledComposite::Static(Box::new(
+            expr.compile_static(registry)?,
+        ))),
+        Some(false) => Ok(CompiledComposite::Dynamic(Box::new(
+            expr.compile_dynamic(key, registry)?,
+        ))),
+        None => Err(invalid(
+            pattern,
+            "a composite must be non-empty and use only static or only dynamic filters".to_string(),
+        )),
+    }
+}
+
+fn invalid(pattern: &str, error: String) -> SettingsError {
+    SettingsError::InvalidFilterExpr {
+        pattern: pattern.to_string(),
+        error,
+    }
+}
+
+/// Compiled composite tree over static or dynamic leaf filters
+pub enum CompositeNode<F> {
+    Leaf(F),
+    All(Vec<CompositeNode<F>>),
+    Any(Vec<CompositeNode<F>>),
+    Not(Box<CompositeNode<F>>),
+}
+
+impl<F> CompositeNode<F> {
+    fn eval(&self, check: &impl Fn(&F) -> bool) -> bool {
+        match self {
+            Self::Leaf(filter) => check(filter),
+            Self::All(nodes) => nodes.iter().all(|n| n.eval(check)),
+            Self::Any(nodes) => nodes.iter().any(|n| n.eval(check)),
+            Self::Not(node) => !node.eval(check),
+        }
+    }
+}
+
+impl CompiledStaticFilter for CompositeNode<Box<dyn CompiledStaticFilter>> {
+    fn check(&self, ctx: &StaticContext) -> bool {
+        self.eval(&|f| f.check(ctx))
+    }
+}
+
+impl CompiledDynamicFilter for CompositeNode<Box<dyn CompiledDynamicFilter>> {
+    fn check(&self, ctx: &DynamicContext) -> bool {
+        self.eval(&|f| f.check(ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{CustomContext, Request};
+    use std::collections::HashMap;
+
+    fn static_ctx(application: &str) -> StaticContext {
+        StaticContext {
+            application: application.to_string(),
+            server: "server-1".to_string(),
+            environment: HashMap::new(),
+            libraries_versions: HashMap::new(),
+            mcs_run_env: None,
+        }
+    }
+
+    fn request_ctx(path: &str, host: &str) -> DynamicContext {
+        DynamicContext {
+            request: Some(Request {
+                method: "GET".to_string(),
+                path: path.to_string(),
+                headers: [("host".to_string(), host.to_string())].into(),
+                ..Default::default()
+            }),
+            custom: CustomContext::new(),
+        }
+    }
+
+    fn dynamic(name: &str, pattern: &str) -> Box<dyn CompiledDynamicFilter> {
+        match compile_composite_filter("KEY", name, pattern, &FilterRegistry::new()).unwrap() {
+            CompiledComposite::Dynamic(f) => f,
+            CompiledComposite::Static(_) => panic!("expected a dynamic composite"),
+        }
+    }
+
+    #[test]
+    fn test_parse_nested_tree() {
+        let expr = FilterExpr::parse(
+            "any",
+            r#"[{"host": "a"}, {"url-path": "/x", "not": {"email": "b"}}]"#,
+        )
+        .unwrap();
+
+        let leaf = |name: &str, pattern: &str| FilterExpr::Leaf {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        };
+        let FilterExpr::Any(items) = expr else {
+            panic!("expected any");
+        };
+        assert_eq!(items[0], leaf("host", "a"));
+        let FilterExpr::All(parts) = &items[1] else {
+            panic!("expected all");
+        };
+        assert!(parts.contains(&leaf("url-path", "/x")));
+        assert!(parts.contains(&FilterExpr::Not(Box::new(leaf("email", "b")))));
+    }
+
+    #[test]
+    fn test_any_dynamic() {
+        let filter = dynamic("any", r#"[{"host": "a\\.com"}, {"url-path": "/admin/.*"}]"#);
+        assert!(filter.check(&request_ctx("/", "a.com")));
+        assert!(filter.check(&request_ctx("/admin/users", "b.com")));
+        assert!(!filter.check(&request_ctx("/", "b.com")));
+    }
+
+    #[test]
+    fn test_not_dynamic() {
+        let filter = dynamic("not", r#"{"url-path": "/internal/.*"}"#);
+        assert!(filter.check(&request_ctx("/api", "a.com")));
+        assert!(!filter.check(&request_ctx("/internal/x", "a.com")));
+    }
+
+    #[test]
+    fn test_all_with_nested_not() {
+        let filter = dynamic(
+            "all",
+            r#"[{"host": "a\\.com"}, {"not": {"url-path": "/internal/.*"}}]"#,
+        );
+        assert!(filter.check(&request_ctx("/api", "a.com")));
+        assert!(!filter.check(&request_ctx("/internal/x", "a.com")));
+        assert!(!filter.check(&request_ctx("/api", "b.com")));
+    }
+
+    #[test]
+    fn test_any_static() {
+        let composite = compile_composite_filter(
+            "KEY",
+            "any",
+            r#"[{"application": "app-a"}, {"application": "app-b"}]"#,
+            &FilterRegistry::new(),
+        )
+        .unwrap();
+        let CompiledComposite::Static(filter) = composite else {
+            panic!("expected a static composite");
+        };
+        assert!(filter.check(&static_ctx("app-a")));
+        assert!(filter.check(&static_ctx("app-b")));
+        assert!(!filter.check(&static_ctx("app-c")));
+    }
+
+    #[test]
+    fn test_mixed_static_and_dynamic_rejected() {
+        let result = compile_composite_filter(
+            "KEY",
+            "any",
+            r#"[{"application": "a"}, {"host": "b"}]"#,
+            &FilterRegistry::new(),
+        );
+        assert!(matches!(
+            result,
+            Err(SettingsError::InvalidFilterExpr { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_composites_rejected() {
+        for (name, pattern) in [
+            ("any", "not json"),
+            ("any", r#"{"host": "a"}"#),
+            ("not", r#"[{"host": "a"}]"#),
+            ("any", r#"[{"host": 1}]"#),
+            ("any", "[]"),
+            ("not", r#"{"unknown-filter": "x"}"#),
+        ] {
+            assert!(
+                compile_composite_filter("KEY", name, pattern, &FilterRegistry::new()).is_err(),
+                "{name}: {pattern} should be rejected"
+            );
+        }
+    }
+}
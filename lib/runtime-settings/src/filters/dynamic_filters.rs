@@ -2,8 +2,12 @@
 use super::{CompiledDynamicFilter, DynamicFilter, FilterResult};
 use crate::context::DynamicContext;
 use crate::error::SettingsError;
+use chrono::{DateTime, Datelike, SecondsFormat, Timelike, Utc};
+use chrono_tz::Tz;
 use rand::Rng;
 use regex::{Regex, RegexBuilder};
+use std::net::IpAddr;
+use std::sync::Arc;
 
 /// Helper to check regex pattern against value (case-insensitive, anchored)
 fn check_regex(pattern: &str, value: &str) -> FilterResult {
@@ -96,6 +100,33 @@ impl DynamicFilter for IpFilter {
     }
 }
 
+/// ip-cidr: comma-separated CIDR blocks against ctx.request.ip()
+pub struct IpCidrFilter;
+
+impl DynamicFilter for IpCidrFilter {
+    fn name(&self) -> &'static str {
+        "ip-cidr"
+    }
+
+    fn check(&self, pattern: &str, ctx: &DynamicContext) -> FilterResult {
+        let ip = match &ctx.request {
+            Some(req) => match req.ip() {
+                Some(ip) => ip,
+                None => return FilterResult::NotApplicable,
+            },
+            None => return FilterResult::NotApplicable,
+        };
+        match parse_ip_nets(pattern) {
+            Ok(nets) if ip_in_nets(&nets, ip) => FilterResult::Match,
+            Ok(_) => FilterResult::NoMatch,
+            Err(e) => {
+                tracing::warn!(pattern = %pattern, error = %e, "Failed to parse CIDR blocks");
+                FilterResult::NoMatch
+            }
+        }
+    }
+}
+
 /// Helper to parse "KEY1=value1,KEY2=value2" and check against a map
 fn check_map_filter(
     pattern: &str,
@@ -261,6 +292,10 @@ impl CompiledDynamicFilter for CompiledUrlPathFilter {
             None => true, // NotApplicable = pass
         }
     }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        ctx.request.as_ref().map(|req| req.path.clone())
+    }
 }
 
 /// Compiled host filter - holds pre-compiled regex, checks against ctx.request.host()
@@ -287,6 +322,10 @@ impl CompiledDynamicFilter for CompiledHostFilter {
             None => true, // NotApplicable = pass
         }
     }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        ctx.request.as_ref()?.host().map(str::to_string)
+    }
 }
 
 /// Compiled email filter - holds pre-compiled regex, checks against ctx.request.email()
@@ -313,6 +352,10 @@ impl CompiledDynamicFilter for CompiledEmailFilter {
             None => true, // NotApplicable = pass
         }
     }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        ctx.request.as_ref()?.email().map(str::to_string)
+    }
 }
 
 /// Compiled ip filter - holds pre-compiled regex, checks against ctx.request.ip()
@@ -339,6 +382,113 @@ impl CompiledDynamicFilter for CompiledIpFilter {
             None => true, // NotApplicable = pass
         }
     }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        ctx.request.as_ref()?.ip().map(str::to_string)
+    }
+}
+
+/// IPv4 or IPv6 network in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNet {
+    /// Parse "10.0.0.0/8", "2001:db8::/32" or a bare address (a single host)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .trim()
+            .parse()
+            .map_err(|e| format!("invalid address {:?}: {}", addr, e))?;
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("invalid prefix length {:?}", len))?,
+            None => max_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+
+    /// Check whether `ip` belongs to this network.
+    /// IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) match IPv4 networks.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parse a comma-separated list of CIDR blocks
+fn parse_ip_nets(pattern: &str) -> Result<Vec<IpNet>, String> {
+    pattern
+        .split(',')
+        .map(str::trim)
+        .filter(|block| !block.is_empty())
+        .map(IpNet::parse)
+        .collect()
+}
+
+/// Check whether a textual IP belongs to any of the networks (unparsable IPs never match)
+fn ip_in_nets(nets: &[IpNet], ip: &str) -> bool {
+    match ip.trim().parse::<IpAddr>() {
+        Ok(ip) => nets.iter().any(|net| net.contains(ip)),
+        Err(_) => false,
+    }
+}
+
+/// Compiled ip-cidr filter - holds parsed networks, checks against ctx.request.ip()
+/// Returns true if no request or no ip (NotApplicable = pass)
+pub struct CompiledIpCidrFilter {
+    nets: Vec<IpNet>,
+}
+
+impl CompiledIpCidrFilter {
+    /// Compile an ip-cidr filter from a pattern like "10.0.0.0/8,2001:db8::/32"
+    pub fn compile(pattern: &str) -> Result<Self, SettingsError> {
+        let nets = parse_ip_nets(pattern).map_err(|error| SettingsError::InvalidIpRange {
+            pattern: pattern.to_string(),
+            error,
+        })?;
+        Ok(Self { nets })
+    }
+}
+
+impl CompiledDynamicFilter for CompiledIpCidrFilter {
+    fn check(&self, ctx: &DynamicContext) -> bool {
+        match &ctx.request {
+            Some(req) => match req.ip() {
+                Some(ip) => ip_in_nets(&self.nets, ip),
+                None => true, // NotApplicable = pass
+            },
+            None => true, // NotApplicable = pass
+        }
+    }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        ctx.request.as_ref()?.ip().map(str::to_string)
+    }
 }
 
 /// Compiled header filter - holds Vec<(lowercase key, compiled regex)> for header matching
@@ -382,15 +532,8 @@ impl CompiledDynamicFilter for CompiledHeaderFilter {
     fn check(&self, ctx: &DynamicContext) -> bool {
         match &ctx.request {
             Some(req) => {
-                // Build lowercase map for case-insensitive matching
-                let headers_lower: std::collections::HashMap<String, &String> = req
-                    .headers
-                    .iter()
-                    .map(|(k, v)| (k.to_lowercase(), v))
-                    .collect();
-
                 for (key, regex) in &self.conditions {
-                    match headers_lower.get(key) {
+                    match req.get_header(key) {
                         Some(actual_value) => {
                             if !regex.is_match(actual_value) {
                                 return false;
@@ -404,6 +547,11 @@ impl CompiledDynamicFilter for CompiledHeaderFilter {
             None => true, // NotApplicable = pass
         }
     }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        let req = ctx.request.as_ref()?;
+        conditions_input(&self.conditions, |key| req.get_header(key))
+    }
 }
 
 /// Compiled context filter - holds Vec<(key, compiled regex)> for custom context matching
@@ -455,6 +603,143 @@ impl CompiledDynamicFilter for CompiledContextFilter {
         }
         true
     }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        conditions_input(&self.conditions, |key| ctx.custom.get(key))
+    }
+}
+
+/// Compiled method filter - holds compiled regex for the HTTP method
+/// Returns true if no request (NotApplicable = pass)
+pub struct CompiledMethodFilter {
+    regex: Regex,
+}
+
+impl CompiledMethodFilter {
+    /// Compile a method filter from a pattern like "GET|HEAD"
+    pub fn compile(pattern: &str) -> Result<Self, SettingsError> {
+        Ok(Self {
+            regex: compile_anchored_regex(pattern)?,
+        })
+    }
+}
+
+impl CompiledDynamicFilter for CompiledMethodFilter {
+    fn check(&self, ctx: &DynamicContext) -> bool {
+        match &ctx.request {
+            Some(req) => self.regex.is_match(&req.method),
+            None => true, // NotApplicable = pass
+        }
+    }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        ctx.request.as_ref().map(|req| req.method.clone())
+    }
+}
+
+/// Parse "KEY1=regex1,KEY2=regex2" into (key, compiled regex) conditions
+fn compile_conditions(pattern: &str) -> Result<Vec<(String, Regex)>, SettingsError> {
+    let mut conditions = Vec::new();
+
+    for pair in pattern.split(',') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+
+        let Some((key, value_pattern)) = pair.split_once('=') else {
+            return Err(SettingsError::InvalidRegex {
+                pattern: pattern.to_string(),
+                error: format!("Invalid KEY=value format: {}", pair),
+            });
+        };
+        conditions.push((
+            key.trim().to_string(),
+            compile_anchored_regex(value_pattern.trim())?,
+        ));
+    }
+
+    Ok(conditions)
+}
+
+/// Check that every condition's key is present and its value matches
+fn check_conditions<'a>(
+    conditions: &[(String, Regex)],
+    lookup: impl Fn(&str) -> Option<&'a str>,
+) -> bool {
+    conditions
+        .iter()
+        .all(|(key, regex)| lookup(key).is_some_and(|value| regex.is_match(value)))
+}
+
+/// Present `key=value` pairs for `conditions`, as reported by `explain()`
+fn conditions_input<'a>(
+    conditions: &[(String, Regex)],
+    lookup: impl Fn(&str) -> Option<&'a str>,
+) -> Option<String> {
+    let pairs: Vec<String> = conditions
+        .iter()
+        .filter_map(|(key, _)| lookup(key).map(|value| format!("{key}={value}")))
+        .collect();
+    (!pairs.is_empty()).then(|| pairs.join(","))
+}
+
+/// Compiled query filter - holds Vec<(key, compiled regex)> for query parameter matching
+/// Keys are case-sensitive. Returns true if no request (NotApplicable = pass)
+pub struct CompiledQueryFilter {
+    conditions: Vec<(String, Regex)>,
+}
+
+impl CompiledQueryFilter {
+    /// Compile a query filter from a pattern like "utm_source=newsletter,ref=.+"
+    pub fn compile(pattern: &str) -> Result<Self, SettingsError> {
+        Ok(Self {
+            conditions: compile_conditions(pattern)?,
+        })
+    }
+}
+
+impl CompiledDynamicFilter for CompiledQueryFilter {
+    fn check(&self, ctx: &DynamicContext) -> bool {
+        match &ctx.request {
+            Some(req) => check_conditions(&self.conditions, |key| req.query_param(key)),
+            None => true, // NotApplicable = pass
+        }
+    }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        let req = ctx.request.as_ref()?;
+        conditions_input(&self.conditions, |key| req.query_param(key))
+    }
+}
+
+/// Compiled cookie filter - holds Vec<(name, compiled regex)> for cookie matching
+/// Names are case-sensitive. Returns true if no request (NotApplicable = pass)
+pub struct CompiledCookieFilter {
+    conditions: Vec<(String, Regex)>,
+}
+
+impl CompiledCookieFilter {
+    /// Compile a cookie filter from a pattern like "beta=1,theme=dark|light"
+    pub fn compile(pattern: &str) -> Result<Self, SettingsError> {
+        Ok(Self {
+            conditions: compile_conditions(pattern)?,
+        })
+    }
+}
+
+impl CompiledDynamicFilter for CompiledCookieFilter {
+    fn check(&self, ctx: &DynamicContext) -> bool {
+        match &ctx.request {
+            Some(req) => check_conditions(&self.conditions, |name| req.cookie(name)),
+            None => true, // NotApplicable = pass
+        }
+    }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        let req = ctx.request.as_ref()?;
+        conditions_input(&self.conditions, |name| req.cookie(name))
+    }
 }
 
 /// Compiled probability filter - holds parsed probability value (0-100)
@@ -490,6 +775,359 @@ impl CompiledDynamicFilter for CompiledProbabilityFilter {
     }
 }
 
+/// Field a rollout buckets on
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RolloutField {
+    Ip,
+    Email,
+    /// Lowercase header name
+    Header(String),
+    Context(String),
+}
+
+impl RolloutField {
+    fn parse(s: &str) -> Option<Self> {
+        match s.split_once(':') {
+            Some(("header", name)) if !name.trim().is_empty() => {
+                Some(Self::Header(name.trim().to_lowercase()))
+            }
+            Some(("context", name)) if !name.trim().is_empty() => {
+                Some(Self::Context(name.trim().to_string()))
+            }
+            None if s == "ip" => Some(Self::Ip),
+            None if s == "email" => Some(Self::Email),
+            _ => None,
+        }
+    }
+
+    fn value<'a>(&self, ctx: &'a DynamicContext) -> Option<&'a str> {
+        match self {
+            Self::Ip => ctx.request.as_ref()?.ip(),
+            Self::Email => ctx.request.as_ref()?.email(),
+            Self::Header(name) => ctx.request.as_ref()?.get_header(name),
+            Self::Context(name) => ctx.custom.get(name),
+        }
+    }
+}
+
+/// Bucket in [0, 100) for a setting key and field value.
+///
+/// Uses FNV-1a, so buckets are stable across processes and Rust versions.
+pub fn rollout_bucket(key: &str, value: &str) -> f64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    for byte in key.bytes().chain([0]).chain(value.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    (hash % 10_000) as f64 / 100.0
+}
+
+/// Compiled rollout filter - deterministic percentage rollout
+/// Hashes the setting key and a request/context field into a bucket, so the same
+/// user consistently falls in or out of the rollout.
+/// Returns false if the field is missing (unidentified callers are not rolled out).
+pub struct CompiledRolloutFilter {
+    key: String,
+    field: RolloutField,
+    percentage: f64,
+}
+
+impl CompiledRolloutFilter {
+    /// Compile a rollout filter for a setting key from a pattern like
+    /// "header:X-User-Id=25", "context:user_id=25", "email=10" or "ip=50"
+    pub fn compile(key: &str, pattern: &str) -> Result<Self, SettingsError> {
+        let invalid = |error: &str| SettingsError::InvalidRegex {
+            pattern: pattern.to_string(),
+            error: error.to_string(),
+        };
+
+        let (field, percentage) = pattern
+            .rsplit_once('=')
+            .ok_or_else(|| invalid("Invalid rollout (expected FIELD=PERCENT)"))?;
+        let field = RolloutField::parse(field.trim()).ok_or_else(|| {
+            invalid("Invalid rollout field (expected ip, email, header:NAME or context:NAME)")
+        })?;
+        let percentage: f64 = percentage
+            .trim()
+            .parse()
+            .ok()
+            .filter(|p: &f64| (0.0..=100.0).contains(p))
+            .ok_or_else(|| invalid("Invalid rollout percentage (expected number 0-100)"))?;
+
+        Ok(Self {
+            key: key.to_string(),
+            field,
+            percentage,
+        })
+    }
+}
+
+impl CompiledDynamicFilter for CompiledRolloutFilter {
+    fn check(&self, ctx: &DynamicContext) -> bool {
+        match self.field.value(ctx) {
+            Some(value) => rollout_bucket(&self.key, value) < self.percentage,
+            None => false,
+        }
+    }
+
+    fn input(&self, ctx: &DynamicContext) -> Option<String> {
+        self.field.value(ctx).map(str::to_string)
+    }
+}
+
+/// Source of the current time for time-based filters
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock reading the system time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock frozen at a fixed instant, for tests
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+fn invalid_schedule(pattern: &str, error: impl Into<String>) -> SettingsError {
+    SettingsError::InvalidSchedule {
+        pattern: pattern.to_string(),
+        error: error.into(),
+    }
+}
+
+/// Current time of `clock` as reported by `explain()`
+fn format_now(clock: &dyn Clock) -> String {
+    clock.now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_timestamp(pattern: &str) -> Result<DateTime<Utc>, SettingsError> {
+    DateTime::parse_from_rfc3339(pattern.trim())
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| invalid_schedule(pattern, format!("Invalid RFC3339 timestamp: {}", e)))
+}
+
+/// Compiled active-from filter - matches from the given instant on
+pub struct CompiledActiveFromFilter {
+    from: DateTime<Utc>,
+    clock: Arc<dyn Clock>,
+}
+
+impl CompiledActiveFromFilter {
+    /// Compile from an RFC3339 timestamp like "2025-06-01T00:00:00Z"
+    pub fn compile(pattern: &str) -> Result<Self, SettingsError> {
+        Self::compile_with_clock(pattern, Arc::new(SystemClock))
+    }
+
+    /// Compile with an explicit clock
+    pub fn compile_with_clock(pattern: &str, clock: Arc<dyn Clock>) -> Result<Self, SettingsError> {
+        Ok(Self {
+            from: parse_timestamp(pattern)?,
+            clock,
+        })
+    }
+}
+
+impl CompiledDynamicFilter for CompiledActiveFromFilter {
+    fn check(&self, _ctx: &DynamicContext) -> bool {
+        self.clock.now() >= self.from
+    }
+
+    fn input(&self, _ctx: &DynamicContext) -> Option<String> {
+        Some(format_now(self.clock.as_ref()))
+    }
+}
+
+/// Compiled active-until filter - matches up to (excluding) the given instant
+pub struct CompiledActiveUntilFilter {
+    until: DateTime<Utc>,
+    clock: Arc<dyn Clock>,
+}
+
+impl CompiledActiveUntilFilter {
+    /// Compile from an RFC3339 timestamp like "2025-06-02T00:00:00+03:00"
+    pub fn compile(pattern: &str) -> Result<Self, SettingsError> {
+        Self::compile_with_clock(pattern, Arc::new(SystemClock))
+    }
+
+    /// Compile with an explicit clock
+    pub fn compile_with_clock(pattern: &str, clock: Arc<dyn Clock>) -> Result<Self, SettingsError> {
+        Ok(Self {
+            until: parse_timestamp(pattern)?,
+            clock,
+        })
+    }
+}
+
+impl CompiledDynamicFilter for CompiledActiveUntilFilter {
+    fn check(&self, _ctx: &DynamicContext) -> bool {
+        self.clock.now() < self.until
+    }
+
+    fn input(&self, _ctx: &DynamicContext) -> Option<String> {
+        Some(format_now(self.clock.as_ref()))
+    }
+}
+
+/// One field of a schedule expression as a bitmask of allowed values
+#[derive(Debug, Clone, Copy)]
+struct ScheduleField {
+    mask: u64,
+    /// Field is `*`
+    any: bool,
+}
+
+impl ScheduleField {
+    /// Parse `*`, `N`, `A-B`, `*/S`, `A-B/S`, `N/S` and comma-separated lists
+    fn parse(field: &str, min: u32, max: u32) -> Result<Self, String> {
+        let mut mask = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step
+                        .parse()
+                        .ok()
+                        .filter(|s| *s > 0)
+                        .ok_or_else(|| format!("invalid step in `{}`", part))?;
+                    (range, Some(step))
+                }
+                None => (part, None),
+            };
+
+            let value = |s: &str| {
+                s.parse::<u32>()
+                    .ok()
+                    .filter(|v| (min..=max).contains(v))
+                    .ok_or_else(|| format!("`{}` is out of range {}-{}", s, min, max))
+            };
+            let (start, end) = match range.split_once('-') {
+                _ if range == "*" => (min, max),
+                Some((start, end)) => (value(start)?, value(end)?),
+                None if step.is_some() => (value(range)?, max),
+                None => {
+                    let v = value(range)?;
+                    (v, v)
+                }
+            };
+            if start > end {
+                return Err(format!("invalid range `{}`", range));
+            }
+
+            for v in (start..=end).step_by(step.unwrap_or(1) as usize) {
+                mask |= 1 << v;
+            }
+        }
+        Ok(Self {
+            mask,
+            any: field == "*",
+        })
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.mask & (1 << value) != 0
+    }
+}
+
+/// Compiled schedule filter - cron-like expression checked against the current minute
+///
+/// Pattern: `MINUTE HOUR DAY-OF-MONTH MONTH DAY-OF-WEEK [TIMEZONE]`, e.g.
+/// `"* 9-17 * * 1-5 Europe/Berlin"` for business hours. Day of week is 0-7
+/// (0 and 7 are Sunday). As in cron, when both day fields are restricted a
+/// day matching either one matches. The timezone is an IANA name, UTC by default.
+pub struct CompiledScheduleFilter {
+    minute: ScheduleField,
+    hour: ScheduleField,
+    day_of_month: ScheduleField,
+    month: ScheduleField,
+    day_of_week: ScheduleField,
+    timezone: Tz,
+    clock: Arc<dyn Clock>,
+}
+
+impl CompiledScheduleFilter {
+    /// Compile from a schedule expression like "0-30 2 * * 0 UTC"
+    pub fn compile(pattern: &str) -> Result<Self, SettingsError> {
+        Self::compile_with_clock(pattern, Arc::new(SystemClock))
+    }
+
+    /// Compile with an explicit clock
+    pub fn compile_with_clock(pattern: &str, clock: Arc<dyn Clock>) -> Result<Self, SettingsError> {
+        let fields: Vec<&str> = pattern.split_whitespace().collect();
+        let (fields, timezone) = match fields.len() {
+            5 => (&fields[..], Tz::UTC),
+            6 => {
+                let timezone = fields[5].parse::<Tz>().map_err(|_| {
+                    invalid_schedule(pattern, format!("Unknown timezone `{}`", fields[5]))
+                })?;
+                (&fields[..5], timezone)
+            }
+            _ => {
+                return Err(invalid_schedule(
+                    pattern,
+                    "Expected MINUTE HOUR DAY-OF-MONTH MONTH DAY-OF-WEEK [TIMEZONE]",
+                ));
+            }
+        };
+
+        let parse = |field: &str, min, max| {
+            ScheduleField::parse(field, min, max).map_err(|e| invalid_schedule(pattern, e))
+        };
+        let mut day_of_week = parse(fields[4], 0, 7)?;
+        // 7 is Sunday, same as 0
+        if day_of_week.contains(7) {
+            day_of_week.mask |= 1;
+        }
+
+        Ok(Self {
+            minute: parse(fields[0], 0, 59)?,
+            hour: parse(fields[1], 0, 23)?,
+            day_of_month: parse(fields[2], 1, 31)?,
+            month: parse(fields[3], 1, 12)?,
+            day_of_week,
+            timezone,
+            clock,
+        })
+    }
+}
+
+impl CompiledDynamicFilter for CompiledScheduleFilter {
+    fn check(&self, _ctx: &DynamicContext) -> bool {
+        let now = self.clock.now().with_timezone(&self.timezone);
+
+        let dom = self.day_of_month.contains(now.day());
+        let dow = self
+            .day_of_week
+            .contains(now.weekday().num_days_from_sunday());
+        let day = if self.day_of_month.any || self.day_of_week.any {
+            dom && dow
+        } else {
+            dom || dow
+        };
+
+        day && self.minute.contains(now.minute())
+            && self.hour.contains(now.hour())
+            && self.month.contains(now.month())
+    }
+
+    /// Current time in the schedule's timezone
+    fn input(&self, _ctx: &DynamicContext) -> Option<String> {
+        let now = self.clock.now().with_timezone(&self.timezone);
+        Some(now.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -502,6 +1140,7 @@ mod tests {
                 method: "GET".to_string(),
                 path: path.to_string(),
                 headers,
+                ..Default::default()
             }),
             custom: CustomContext::new(),
         }
@@ -558,6 +1197,19 @@ mod tests {
         assert_eq!(filter.check("192\\.168\\..*", &ctx), FilterResult::Match);
     }
 
+    #[test]
+    fn test_ip_cidr_filter_match() {
+        let filter = IpCidrFilter;
+        let mut headers = HashMap::new();
+        headers.insert("x-real-ip".to_string(), "192.168.1.100".to_string());
+        let ctx = make_ctx_with_request("/", headers);
+        assert_eq!(
+            filter.check("10.0.0.0/8,192.168.0.0/16", &ctx),
+            FilterResult::Match
+        );
+        assert_eq!(filter.check("10.0.0.0/8", &ctx), FilterResult::NoMatch);
+    }
+
     #[test]
     fn test_header_filter_match() {
         let filter = HeaderFilter;
@@ -645,6 +1297,7 @@ mod compiled_dynamic_tests {
                 method: "GET".to_string(),
                 path: path.to_string(),
                 headers,
+                ..Default::default()
             }),
             custom: CustomContext::new(),
         }
@@ -793,6 +1446,89 @@ mod compiled_dynamic_tests {
         assert!(filter.check(&ctx));
     }
 
+    // IpNet / CompiledIpCidrFilter tests
+    fn ctx_with_ip(ip: &str) -> DynamicContext {
+        let mut headers = HashMap::new();
+        headers.insert("x-real-ip".to_string(), ip.to_string());
+        make_ctx_with_request("/", headers)
+    }
+
+    #[test]
+    fn test_ip_net_contains_v4() {
+        let net = IpNet::parse("10.0.0.0/8").unwrap();
+        assert!(net.contains("10.1.2.3".parse().unwrap()));
+        assert!(!net.contains("11.0.0.1".parse().unwrap()));
+
+        let net = IpNet::parse("192.168.1.0/24").unwrap();
+        assert!(net.contains("192.168.1.255".parse().unwrap()));
+        assert!(!net.contains("192.168.2.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ip_net_contains_v6() {
+        let net = IpNet::parse("2001:db8::/32").unwrap();
+        assert!(net.contains("2001:db8:1::1".parse().unwrap()));
+        assert!(!net.contains("2001:db9::1".parse().unwrap()));
+        assert!(!net.contains("10.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ip_net_bare_address_and_zero_prefix() {
+        let host = IpNet::parse("10.0.0.1").unwrap();
+        assert!(host.contains("10.0.0.1".parse().unwrap()));
+        assert!(!host.contains("10.0.0.2".parse().unwrap()));
+
+        let any = IpNet::parse("0.0.0.0/0").unwrap();
+        assert!(any.contains("203.0.113.7".parse().unwrap()));
+        let any_v6 = IpNet::parse("::/0").unwrap();
+        assert!(any_v6.contains("2001:db8::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ip_net_mapped_v4_matches_v4_block() {
+        let net = IpNet::parse("10.0.0.0/8").unwrap();
+        assert!(net.contains("::ffff:10.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ip_net_parse_invalid() {
+        assert!(IpNet::parse("10.0.0.0/33").is_err());
+        assert!(IpNet::parse("2001:db8::/129").is_err());
+        assert!(IpNet::parse("10.0.0/8").is_err());
+        assert!(IpNet::parse("10.0.0.0/abc").is_err());
+    }
+
+    #[test]
+    fn test_compiled_ip_cidr_filter_multiple_blocks() {
+        let filter =
+            CompiledIpCidrFilter::compile("10.0.0.0/8, 192.168.1.0/24,2001:db8::/32").unwrap();
+        assert!(filter.check(&ctx_with_ip("10.20.30.40")));
+        assert!(filter.check(&ctx_with_ip("192.168.1.100")));
+        assert!(filter.check(&ctx_with_ip("2001:db8::42")));
+        assert!(!filter.check(&ctx_with_ip("192.168.2.1")));
+        assert!(!filter.check(&ctx_with_ip("172.16.0.1")));
+    }
+
+    #[test]
+    fn test_compiled_ip_cidr_filter_unparsable_ip_no_match() {
+        let filter = CompiledIpCidrFilter::compile("10.0.0.0/8").unwrap();
+        assert!(!filter.check(&ctx_with_ip("10.0.0.1:8080")));
+        assert!(!filter.check(&ctx_with_ip("not-an-ip")));
+    }
+
+    #[test]
+    fn test_compiled_ip_cidr_filter_no_ip_returns_true() {
+        let filter = CompiledIpCidrFilter::compile("10.0.0.0/8").unwrap();
+        assert!(filter.check(&DynamicContext::default()));
+        assert!(filter.check(&make_ctx_with_request("/", HashMap::new())));
+    }
+
+    #[test]
+    fn test_compiled_ip_cidr_filter_invalid_pattern() {
+        let result = CompiledIpCidrFilter::compile("10.0.0.0/8,300.0.0.0/8");
+        assert!(matches!(result, Err(SettingsError::InvalidIpRange { .. })));
+    }
+
     // CompiledHeaderFilter tests
     #[test]
     fn test_compiled_header_filter_match() {
@@ -1002,4 +1738,258 @@ mod compiled_dynamic_tests {
         // Run multiple times, one of them should pass (though randomness applies)
         let _ = filter.check(&ctx);
     }
+
+    // CompiledRolloutFilter tests
+    fn ctx_with_user(user_id: &str) -> DynamicContext {
+        let mut headers = HashMap::new();
+        headers.insert("X-User-Id".to_string(), user_id.to_string());
+        make_ctx_with_request("/", headers)
+    }
+
+    #[test]
+    fn test_rollout_bucket_is_stable() {
+        // Known value: buckets must not change between releases
+        assert_eq!(rollout_bucket("FEATURE", "user-1"), 10.22);
+        assert_eq!(
+            rollout_bucket("FEATURE", "user-1"),
+            rollout_bucket("FEATURE", "user-1")
+        );
+    }
+
+    #[test]
+    fn test_compiled_rollout_filter_is_sticky() {
+        let filter = CompiledRolloutFilter::compile("FEATURE", "header:x-user-id=50").unwrap();
+        for user in ["alice", "bob", "carol", "dave"] {
+            let ctx = ctx_with_user(user);
+            let first = filter.check(&ctx);
+            for _ in 0..20 {
+                assert_eq!(filter.check(&ctx), first);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compiled_rollout_filter_percentage_bounds() {
+        let none = CompiledRolloutFilter::compile("FEATURE", "header:X-User-Id=0").unwrap();
+        let all = CompiledRolloutFilter::compile("FEATURE", "header:X-User-Id=100").unwrap();
+        for i in 0..100 {
+            let ctx = ctx_with_user(&format!("user-{i}"));
+            assert!(!none.check(&ctx));
+            assert!(all.check(&ctx));
+        }
+    }
+
+    #[test]
+    fn test_compiled_rollout_filter_distribution() {
+        let filter = CompiledRolloutFilter::compile("FEATURE", "context:user_id=25").unwrap();
+        let enabled = (0..10_000)
+            .filter(|i| {
+                let mut custom = CustomContext::new();
+                custom.push_layer([("user_id".to_string(), i.to_string())].into());
+                filter.check(&DynamicContext {
+                    request: None,
+                    custom,
+                })
+            })
+            .count();
+        assert!((2_000..3_000).contains(&enabled), "enabled = {enabled}");
+    }
+
+    #[test]
+    fn test_compiled_rollout_filter_depends_on_key() {
+        let a = CompiledRolloutFilter::compile("FEATURE_A", "email=50").unwrap();
+        let b = CompiledRolloutFilter::compile("FEATURE_B", "email=50").unwrap();
+        let differs = (0..200).any(|i| {
+            let mut headers = HashMap::new();
+            headers.insert("x-real-email".to_string(), format!("user{i}@example.com"));
+            let ctx = make_ctx_with_request("/", headers);
+            a.check(&ctx) != b.check(&ctx)
+        });
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_compiled_rollout_filter_missing_field_returns_false() {
+        let filter = CompiledRolloutFilter::compile("FEATURE", "ip=100").unwrap();
+        assert!(!filter.check(&DynamicContext::default()));
+        assert!(!filter.check(&make_ctx_with_request("/", HashMap::new())));
+    }
+
+    #[test]
+    fn test_compiled_rollout_filter_invalid() {
+        assert!(CompiledRolloutFilter::compile("K", "25").is_err());
+        assert!(CompiledRolloutFilter::compile("K", "cookie:id=25").is_err());
+        assert!(CompiledRolloutFilter::compile("K", "header:=25").is_err());
+        assert!(CompiledRolloutFilter::compile("K", "ip=abc").is_err());
+        for percentage in ["150", "100.5", "-5", "NaN", "inf", "-inf"] {
+            let pattern = format!("ip={percentage}");
+            assert!(
+                CompiledRolloutFilter::compile("K", &pattern).is_err(),
+                "{pattern}"
+            );
+        }
+        assert!(CompiledRolloutFilter::compile("K", "ip=0").is_ok());
+        assert!(CompiledRolloutFilter::compile("K", "ip=100").is_ok());
+    }
+
+    fn request_ctx(request: Request) -> DynamicContext {
+        DynamicContext {
+            request: Some(request),
+            custom: CustomContext::new(),
+        }
+    }
+
+    #[test]
+    fn test_compiled_method_filter() {
+        let filter = CompiledMethodFilter::compile("GET|HEAD").unwrap();
+        let method = |method: &str| {
+            request_ctx(Request {
+                method: method.to_string(),
+                ..Default::default()
+            })
+        };
+        assert!(filter.check(&method("GET")));
+        assert!(filter.check(&method("head")));
+        assert!(!filter.check(&method("POST")));
+        assert!(filter.check(&DynamicContext::default()));
+    }
+
+    #[test]
+    fn test_compiled_query_filter() {
+        let filter = CompiledQueryFilter::compile("utm_source=news.*,ref=.+").unwrap();
+        let query = |qs: &str| {
+            request_ctx(Request {
+                query: Request::parse_query(qs),
+                ..Default::default()
+            })
+        };
+        assert!(filter.check(&query("utm_source=newsletter&ref=abc")));
+        assert!(!filter.check(&query("utm_source=newsletter")));
+        assert!(!filter.check(&query("utm_source=ads&ref=abc")));
+        // Parameter names are case-sensitive
+        assert!(!filter.check(&query("UTM_SOURCE=newsletter&ref=abc")));
+        assert!(filter.check(&DynamicContext::default()));
+    }
+
+    #[test]
+    fn test_compiled_cookie_filter() {
+        let filter = CompiledCookieFilter::compile("beta=1").unwrap();
+        let cookies = |header: &str| {
+            request_ctx(Request {
+                cookies: Request::parse_cookies(header),
+                ..Default::default()
+            })
+        };
+        assert!(filter.check(&cookies("session=abc; beta=1")));
+        assert!(!filter.check(&cookies("beta=0")));
+        assert!(!filter.check(&cookies("session=abc")));
+        assert!(filter.check(&DynamicContext::default()));
+    }
+
+    #[test]
+    fn test_compiled_query_filter_invalid() {
+        assert!(CompiledQueryFilter::compile("utm_source").is_err());
+        assert!(CompiledCookieFilter::compile("beta=[").is_err());
+    }
+
+    fn clock_at(time: &str) -> Arc<dyn Clock> {
+        Arc::new(FixedClock(
+            DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .with_timezone(&Utc),
+        ))
+    }
+
+    fn schedule_at(pattern: &str, time: &str) -> bool {
+        CompiledScheduleFilter::compile_with_clock(pattern, clock_at(time))
+            .unwrap()
+            .check(&DynamicContext::default())
+    }
+
+    #[test]
+    fn test_compiled_active_from_until_filters() {
+        let ctx = DynamicContext::default();
+        let from = |time| {
+            CompiledActiveFromFilter::compile_with_clock("2025-06-01T00:00:00Z", clock_at(time))
+                .unwrap()
+        };
+        let until = |time| {
+            CompiledActiveUntilFilter::compile_with_clock(
+                "2025-06-01T03:00:00+03:00",
+                clock_at(time),
+            )
+            .unwrap()
+        };
+
+        assert!(!from("2025-05-31T23:59:59Z").check(&ctx));
+        assert!(from("2025-06-01T00:00:00Z").check(&ctx));
+        assert!(until("2025-05-31T23:59:59Z").check(&ctx));
+        assert!(!until("2025-06-01T00:00:00Z").check(&ctx));
+    }
+
+    #[test]
+    fn test_compiled_active_from_invalid() {
+        assert!(matches!(
+            CompiledActiveFromFilter::compile("2025-06-01"),
+            Err(SettingsError::InvalidSchedule { .. })
+        ));
+        assert!(CompiledActiveUntilFilter::compile("tomorrow").is_err());
+    }
+
+    #[test]
+    fn test_compiled_schedule_filter_business_hours() {
+        // 2025-06-02 is a Monday
+        let pattern = "* 9-17 * * 1-5";
+        assert!(schedule_at(pattern, "2025-06-02T09:00:00Z"));
+        assert!(schedule_at(pattern, "2025-06-02T17:59:00Z"));
+        assert!(!schedule_at(pattern, "2025-06-02T18:00:00Z"));
+        assert!(!schedule_at(pattern, "2025-06-01T12:00:00Z"));
+    }
+
+    #[test]
+    fn test_compiled_schedule_filter_timezone() {
+        // 09:00 in Berlin is 07:00 UTC in summer
+        let pattern = "* 9 * * * Europe/Berlin";
+        assert!(schedule_at(pattern, "2025-06-02T07:30:00Z"));
+        assert!(!schedule_at(pattern, "2025-06-02T09:30:00Z"));
+    }
+
+    #[test]
+    fn test_compiled_schedule_filter_steps_and_lists() {
+        let pattern = "*/15,50 * * * *";
+        assert!(schedule_at(pattern, "2025-06-02T10:00:00Z"));
+        assert!(schedule_at(pattern, "2025-06-02T10:45:00Z"));
+        assert!(schedule_at(pattern, "2025-06-02T10:50:00Z"));
+        assert!(!schedule_at(pattern, "2025-06-02T10:20:00Z"));
+    }
+
+    #[test]
+    fn test_compiled_schedule_filter_day_fields() {
+        // Sunday as 7, or the 1st of the month
+        let pattern = "* * 1 * 7";
+        assert!(schedule_at(pattern, "2025-06-01T12:00:00Z"));
+        assert!(schedule_at(pattern, "2025-06-08T12:00:00Z"));
+        assert!(schedule_at(pattern, "2025-07-01T12:00:00Z"));
+        assert!(!schedule_at(pattern, "2025-06-02T12:00:00Z"));
+    }
+
+    #[test]
+    fn test_compiled_schedule_filter_invalid() {
+        for pattern in [
+            "* * * *",
+            "60 * * * *",
+            "* 5-2 * * *",
+            "*/0 * * * *",
+            "* * 0 * *",
+            "* * * * * Mars/Olympus",
+        ] {
+            assert!(
+                matches!(
+                    CompiledScheduleFilter::compile(pattern),
+                    Err(SettingsError::InvalidSchedule { .. })
+                ),
+                "{pattern} should be rejected"
+            );
+        }
+    }
 }
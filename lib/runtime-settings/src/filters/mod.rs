@@ -1,5 +1,7 @@
 // lib/runtime-settings/src/filters/mod.rs
+pub mod composite;
 pub mod dynamic_filters;
+pub mod registry;
 pub mod static_filters;
 
 use crate::context::{DynamicContext, StaticContext};
@@ -34,22 +36,47 @@ pub trait CompiledStaticFilter: Send + Sync {
 /// Trait for pre-compiled dynamic filters
 pub trait CompiledDynamicFilter: Send + Sync {
     fn check(&self, ctx: &DynamicContext) -> bool;
+
+    /// Value `check` reads from `ctx`, reported by `explain()`
+    fn input(&self, _ctx: &DynamicContext) -> Option<String> {
+        None
+    }
 }
 
+pub use composite::*;
 pub use dynamic_filters::*;
+pub use registry::*;
 pub use static_filters::*;
 
 /// Check all static filters against context. Returns true if all static filters match.
 /// Non-static filters (dynamic filters, unknown filters) are skipped.
-pub fn check_static_filters(filters: &HashMap<String, String>, ctx: &StaticContext) -> bool {
+pub fn check_static_filters(
+    filters: &HashMap<String, String>,
+    ctx: &StaticContext,
+    registry: &FilterRegistry,
+) -> bool {
     for (name, pattern) in filters {
+        // Composites of static filters are checked here, dynamic ones skipped
+        if is_composite_filter(name) {
+            match compile_composite_filter("", name, pattern, registry) {
+                Ok(CompiledComposite::Static(compiled)) => {
+                    if !compiled.check(ctx) {
+                        return false;
+                    }
+                }
+                Ok(CompiledComposite::Dynamic(_)) => {}
+                Err(_) => return false,
+            }
+            continue;
+        }
+
         // Only check known static filters, skip everything else
-        if !is_static_filter(name) {
+        if !is_static_filter(name, registry) {
             continue;
         }
 
         // Compile and check the static filter
-        match compile_static_filter(name, pattern) {
+        match compile_static_filter(name, pattern, registry) {
             Ok(compiled) => {
                 if !compiled.check(ctx) {
                     return false;
@@ -77,15 +104,49 @@ const KNOWN_STATIC_FILTER_NAMES: &[&str] = &[
     "library_version",
 ];
 
-/// Check if a filter name is static
-pub fn is_static_filter(name: &str) -> bool {
+/// Known dynamic filter names
+const KNOWN_DYNAMIC_FILTER_NAMES: &[&str] = &[
+    "url-path",
+    "host",
+    "email",
+    "ip",
+    "ip-cidr",
+    "header",
+    "context",
+    "probability",
+    "rollout",
+    "method",
+    "query",
+    "cookie",
+    "active-from",
+    "active-until",
+    "schedule",
+];
+
+/// Check if a filter name is static, including registered custom static filters
+pub fn is_static_filter(name: &str, registry: &FilterRegistry) -> bool {
+    KNOWN_STATIC_FILTER_NAMES.contains(&name)
+        || matches!(registry.get(name), Some(CustomFilter::Static(_)))
+}
+
+/// Check if a filter name is dynamic, including registered custom dynamic filters
+pub fn is_dynamic_filter(name: &str, registry: &FilterRegistry) -> bool {
+    KNOWN_DYNAMIC_FILTER_NAMES.contains(&name)
+        || matches!(registry.get(name), Some(CustomFilter::Dynamic(_)))
+}
+
+/// Check if a filter name belongs to a built-in or composite filter
+pub fn is_builtin_filter(name: &str) -> bool {
     KNOWN_STATIC_FILTER_NAMES.contains(&name)
+        || KNOWN_DYNAMIC_FILTER_NAMES.contains(&name)
+        || is_composite_filter(name)
 }
 
 /// Compile a static filter by name
 pub fn compile_static_filter(
     name: &str,
     pattern: &str,
+    registry: &FilterRegistry,
 ) -> Result<Box<dyn CompiledStaticFilter>, SettingsError> {
     match name {
         "application" => Ok(Box::new(CompiledApplicationFilter::compile(pattern)?)),
@@ -93,10 +154,13 @@ pub fn compile_static_filter(
         "mcs_run_env" => Ok(Box::new(CompiledMcsRunEnvFilter::compile(pattern)?)),
         "environment" => Ok(Box::new(CompiledEnvironmentFilter::compile(pattern)?)),
         "library_version" => Ok(Box::new(CompiledLibraryVersionFilter::compile(pattern)?)),
-        _ => Err(SettingsError::InvalidRegex {
-            pattern: pattern.to_string(),
-            error: format!("Unknown static filter: {}", name),
-        }),
+        _ => match registry.get(name) {
+            Some(CustomFilter::Static(compile)) => compile(pattern),
+            _ => Err(SettingsError::InvalidRegex {
+                pattern: pattern.to_string(),
+                error: format!("Unknown static filter: {}", name),
+            }),
+        },
     }
 }
 
@@ -104,19 +168,51 @@ pub fn compile_static_filter(
 pub fn compile_dynamic_filter(
     name: &str,
     pattern: &str,
+    registry: &FilterRegistry,
+) -> Result<Box<dyn CompiledDynamicFilter>, SettingsError> {
+    compile_dynamic_filter_for_key("", name, pattern, registry)
+}
+
+/// Compile a dynamic filter by name for a setting key.
+/// Filters that depend on the key (such as `rollout`) need this variant.
+pub fn compile_dynamic_filter_for_key(
+    key: &str,
+    name: &str,
+    pattern: &str,
+    registry: &FilterRegistry,
 ) -> Result<Box<dyn CompiledDynamicFilter>, SettingsError> {
     match name {
         "url-path" => Ok(Box::new(CompiledUrlPathFilter::compile(pattern)?)),
         "host" => Ok(Box::new(CompiledHostFilter::compile(pattern)?)),
         "email" => Ok(Box::new(CompiledEmailFilter::compile(pattern)?)),
         "ip" => Ok(Box::new(CompiledIpFilter::compile(pattern)?)),
+        "ip-cidr" => Ok(Box::new(CompiledIpCidrFilter::compile(pattern)?)),
         "header" => Ok(Box::new(CompiledHeaderFilter::compile(pattern)?)),
         "context" => Ok(Box::new(CompiledContextFilter::compile(pattern)?)),
+        "method" => Ok(Box::new(CompiledMethodFilter::compile(pattern)?)),
+        "query" => Ok(Box::new(CompiledQueryFilter::compile(pattern)?)),
+        "cookie" => Ok(Box::new(CompiledCookieFilter::compile(pattern)?)),
         "probability" => Ok(Box::new(CompiledProbabilityFilter::compile(pattern)?)),
-        _ => Err(SettingsError::InvalidRegex {
-            pattern: pattern.to_string(),
-            error: format!("Unknown dynamic filter: {}", name),
-        }),
+        "rollout" => Ok(Box::new(CompiledRolloutFilter::compile(key, pattern)?)),
+        "active-from" => Ok(Box::new(CompiledActiveFromFilter::compile_with_clock(
+            pattern,
+            registry.clock().clone(),
+        )?)),
+        "active-until" => Ok(Box::new(CompiledActiveUntilFilter::compile_with_clock(
+            pattern,
+            registry.clock().clone(),
+        )?)),
+        "schedule" => Ok(Box::new(CompiledScheduleFilter::compile_with_clock(
+            pattern,
+            registry.clock().clone(),
+        )?)),
+        _ => match registry.get(name) {
+            Some(CustomFilter::Dynamic(compile)) => compile(pattern),
+            _ => Err(SettingsError::InvalidRegex {
+                pattern: pattern.to_string(),
+                error: format!("Unknown dynamic filter: {}", name),
+            }),
+        },
     }
 }
 
@@ -141,7 +237,7 @@ mod tests {
             mcs_run_env: None,
         };
 
-        assert!(check_static_filters(&filters, &ctx));
+        assert!(check_static_filters(&filters, &ctx, &FilterRegistry::new()));
     }
 
     #[test]
@@ -160,7 +256,11 @@ mod tests {
             mcs_run_env: None,
         };
 
-        assert!(!check_static_filters(&filters, &ctx));
+        assert!(!check_static_filters(
+            &filters,
+            &ctx,
+            &FilterRegistry::new()
+        ));
     }
 
     #[test]
@@ -178,7 +278,7 @@ mod tests {
         };
 
         // Dynamic filters are skipped, so this should pass
-        assert!(check_static_filters(&filters, &ctx));
+        assert!(check_static_filters(&filters, &ctx, &FilterRegistry::new()));
     }
 
     #[test]
@@ -195,6 +295,33 @@ mod tests {
         };
 
         // Unknown filters should be ignored (return true)
-        assert!(check_static_filters(&filters, &ctx));
+        assert!(check_static_filters(&filters, &ctx, &FilterRegistry::new()));
+    }
+
+    #[test]
+    fn test_check_static_filters_composite() {
+        let ctx = StaticContext {
+            application: "app-b".to_string(),
+            server: "server".to_string(),
+            environment: HashMap::new(),
+            libraries_versions: HashMap::new(),
+            mcs_run_env: None,
+        };
+
+        let any: HashMap<String, String> = [(
+            "any".to_string(),
+            r#"[{"application": "app-a"}, {"application": "app-b"}]"#.to_string(),
+        )]
+        .into();
+        assert!(check_static_filters(&any, &ctx, &FilterRegistry::new()));
+
+        let not: HashMap<String, String> =
+            [("not".to_string(), r#"{"application": "app-b"}"#.to_string())].into();
+        assert!(!check_static_filters(&not, &ctx, &FilterRegistry::new()));
+
+        // Dynamic composites are skipped
+        let dynamic: HashMap<String, String> =
+            [("not".to_string(), r#"{"url-path": "/.*"}"#.to_string())].into();
+        assert!(check_static_filters(&dynamic, &ctx, &FilterRegistry::new()));
     }
 }
@@ -0,0 +1,126 @@
+// lib/runtime-settings/src/filters/registry.rs
+//! Registry of custom filters.
+//!
+//! Custom filters are registered on `RuntimeSettingsBuilder` and stored on the
+//! `RuntimeSettings` it builds. Each instance only sees its own filters; the
+//! registry is passed to `Setting::compile`, `check_static_filters` and
+//! `explain`. It also carries the clock used by time-based filters.
+
+use super::{Clock, CompiledDynamicFilter, CompiledStaticFilter, SystemClock, is_builtin_filter};
+use crate::error::SettingsError;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Compile function for a custom static filter, called with the filter pattern
+pub type StaticFilterFactory =
+    Arc<dyn Fn(&str) -> Result<Box<dyn CompiledStaticFilter>, SettingsError> + Send + Sync>;
+
+/// Compile function for a custom dynamic filter, called with the filter pattern
+pub type DynamicFilterFactory =
+    Arc<dyn Fn(&str) -> Result<Box<dyn CompiledDynamicFilter>, SettingsError> + Send + Sync>;
+
+/// A custom filter compile function
+#[derive(Clone)]
+pub enum CustomFilter {
+    Static(StaticFilterFactory),
+    Dynamic(DynamicFilterFactory),
+}
+
+/// Custom filters and the clock available to one `RuntimeSettings` instance
+#[derive(Clone)]
+pub struct FilterRegistry {
+    custom: HashMap<String, CustomFilter>,
+    clock: Arc<dyn Clock>,
+}
+
+impl FilterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `clock` for `active-from`, `active-until` and `schedule` filters
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Clock for time-based filters
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Add a custom filter, replacing any custom filter with the same name.
+    ///
+    /// Built-in filter names cannot be overridden.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        filter: CustomFilter,
+    ) -> Result<(), SettingsError> {
+        let name = name.into();
+        if is_builtin_filter(&name) {
+            return Err(SettingsError::ReservedFilterName { name });
+        }
+        self.custom.insert(name, filter);
+        Ok(())
+    }
+
+    /// Look up a custom filter by name
+    pub fn get(&self, name: &str) -> Option<&CustomFilter> {
+        self.custom.get(name)
+    }
+}
+
+impl Default for FilterRegistry {
+    fn default() -> Self {
+        Self {
+            custom: HashMap::new(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::StaticContext;
+
+    struct Always(bool);
+
+    impl CompiledStaticFilter for Always {
+        fn check(&self, _ctx: &StaticContext) -> bool {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_register_and_lookup() {
+        let mut registry = FilterRegistry::new();
+        let factory: StaticFilterFactory =
+            Arc::new(|pattern| Ok(Box::new(Always(pattern == "yes"))));
+        registry
+            .register("datacenter", CustomFilter::Static(factory))
+            .unwrap();
+
+        assert!(matches!(
+            registry.get("datacenter"),
+            Some(CustomFilter::Static(_))
+        ));
+        assert!(registry.get("missing").is_none());
+
+        // Registries are independent
+        assert!(FilterRegistry::new().get("datacenter").is_none());
+    }
+
+    #[test]
+    fn test_builtin_names_reserved() {
+        let mut registry = FilterRegistry::new();
+        for name in ["application", "host", "any"] {
+            let factory: StaticFilterFactory = Arc::new(|_| Ok(Box::new(Always(true))));
+            assert!(matches!(
+                registry.register(name, CustomFilter::Static(factory)),
+                Err(SettingsError::ReservedFilterName { .. })
+            ));
+        }
+    }
+}
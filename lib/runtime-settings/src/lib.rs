@@ -2,25 +2,33 @@
 pub mod context;
 pub mod entities;
 pub mod error;
+pub mod explain;
 pub mod filters;
 pub mod providers;
 pub mod scoped;
 pub mod secrets;
 pub mod settings;
 pub mod setup;
+pub mod validation;
 pub mod watchers;
 
 pub use context::{CustomContext, DynamicContext, Request, StaticContext};
 pub use entities::{McsResponse, RawSetting, Setting, SettingKey};
 pub use error::SettingsError;
-pub use filters::{check_static_filters, FilterResult};
+pub use explain::{CandidateTrace, Explanation, FilterKind, FilterOutcome, FilterTrace};
+pub use filters::{
+    Clock, CompiledDynamicFilter, CompiledStaticFilter, CustomFilter, FilterRegistry, FilterResult,
+    FixedClock, SystemClock, check_static_filters,
+};
 pub use providers::{ProviderResponse, SettingsProvider};
+pub use runtime_settings_derive::RuntimeSettings;
 pub use scoped::{
-    current_custom, current_request, set_thread_custom, set_thread_request, with_task_custom,
-    with_task_request, CustomContextGuard, RequestGuard,
+    CustomContextGuard, RequestGuard, current_custom, current_request, set_thread_custom,
+    set_thread_request, with_task_custom, with_task_request,
 };
-pub use secrets::{resolve_secrets, SecretsService};
+pub use secrets::{Secret, SecretsPrefetch, SecretsService, resolve_secrets};
 pub use settings::{RuntimeSettings, RuntimeSettingsBuilder};
-pub use vault_client::{VaultClient, VaultClientBuilder};
 pub use setup::{settings, setup, setup_from_env};
-pub use watchers::{Watcher, WatcherId, WatchersService};
+pub use validation::{RejectHook, SettingRejected, ValueValidator};
+pub use vault_client::{VaultClient, VaultClientBuilder};
+pub use watchers::{PatternWatcher, Watcher, WatcherId, WatchersService};
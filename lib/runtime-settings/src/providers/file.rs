@@ -1,6 +1,6 @@
 // lib/runtime-settings/src/providers/file.rs
 use super::{ProviderResponse, SettingsProvider};
-use crate::entities::RawSetting;
+use crate::entities::{RawSetting, deserialize_filter};
 use crate::error::SettingsError;
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -15,7 +15,7 @@ struct FileSetting {
     key: String,
     #[serde(default)]
     priority: Option<i64>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_filter")]
     filter: HashMap<String, String>,
     value: serde_json::Value,
 }
@@ -134,4 +134,27 @@ mod tests {
 
         assert_eq!(response.settings.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_file_provider_composite_filter() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"[
+            {{
+                "key": "KEY",
+                "value": 1,
+                "filter": {{"not": {{"url-path": "/internal/.*"}}}},
+            }}
+        ]"#
+        )
+        .unwrap();
+
+        let provider = FileProvider::new(file.path().to_path_buf());
+        let response = provider.load("").await.unwrap();
+
+        let not: serde_json::Value =
+            serde_json::from_str(&response.settings[0].filter["not"]).unwrap();
+        assert_eq!(not, serde_json::json!({"url-path": "/internal/.*"}));
+    }
 }
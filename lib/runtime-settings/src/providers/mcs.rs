@@ -5,8 +5,12 @@ use crate::entities::McsResponse;
 use crate::error::SettingsError;
 use async_trait::async_trait;
 use serde::Serialize;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Extra time on top of the long-poll timeout before the HTTP request is abandoned
+const LONG_POLL_GRACE: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Serialize)]
 struct McsRequest {
     runtime: String,
@@ -15,12 +19,16 @@ struct McsRequest {
     application: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     mcs_run_env: Option<String>,
+    /// Long-poll: how long MCS may hold the request waiting for a change (seconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<u64>,
 }
 
 pub struct McsProvider {
     base_url: String,
     application: String,
     mcs_run_env: Option<String>,
+    long_poll_timeout: Option<Duration>,
     client: reqwest::Client,
 }
 
@@ -30,10 +38,35 @@ impl McsProvider {
             base_url,
             application,
             mcs_run_env,
+            long_poll_timeout: None,
             client: reqwest::Client::new(),
         }
     }
 
+    /// Enable push mode: changes are long-polled from `/v3/watch-runtime-settings/`,
+    /// which MCS holds open for up to `timeout` until the version changes.
+    pub fn with_long_poll(mut self, timeout: Duration) -> Self {
+        self.long_poll_timeout = Some(timeout);
+        self
+    }
+
+    fn request(&self, current_version: &str, timeout: Option<Duration>) -> McsRequest {
+        McsRequest {
+            runtime: "rust".to_string(),
+            version: current_version.to_string(),
+            application: Some(self.application.clone()),
+            mcs_run_env: self.mcs_run_env.clone(),
+            timeout: timeout.map(|t| t.as_secs()),
+        }
+    }
+
+    async fn error_response(response: reqwest::Response) -> SettingsError {
+        SettingsError::McsResponse {
+            status: response.status().as_u16(),
+            message: response.text().await.unwrap_or_default(),
+        }
+    }
+
     /// Create from environment variables
     ///
     /// Returns None if RUNTIME_SETTINGS_BASE_URL is not set.
@@ -49,12 +82,7 @@ impl SettingsProvider for McsProvider {
     async fn load(&self, current_version: &str) -> Result<ProviderResponse, SettingsError> {
         let url = format!("{}/v3/get-runtime-settings/", self.base_url);
 
-        let request = McsRequest {
-            runtime: "rust".to_string(),
-            version: current_version.to_string(),
-            application: Some(self.application.clone()),
-            mcs_run_env: self.mcs_run_env.clone(),
-        };
+        let request = self.request(current_version, None);
 
         let operation_id = Uuid::new_v4().to_string();
         let response = self
@@ -66,10 +94,7 @@ impl SettingsProvider for McsProvider {
             .await?;
 
         if !response.status().is_success() {
-            return Err(SettingsError::McsResponse {
-                status: response.status().as_u16(),
-                message: response.text().await.unwrap_or_default(),
-            });
+            return Err(Self::error_response(response).await);
         }
 
         let mcs_response: McsResponse = response.json().await?;
@@ -81,6 +106,51 @@ impl SettingsProvider for McsProvider {
         })
     }
 
+    fn supports_push(&self) -> bool {
+        self.long_poll_timeout.is_some()
+    }
+
+    async fn wait_for_changes(
+        &self,
+        current_version: &str,
+    ) -> Result<Option<ProviderResponse>, SettingsError> {
+        let Some(timeout) = self.long_poll_timeout else {
+            return Ok(None);
+        };
+
+        let url = format!("{}/v3/watch-runtime-settings/", self.base_url);
+        let request = self.request(current_version, Some(timeout));
+
+        let operation_id = Uuid::new_v4().to_string();
+        let response = self
+            .client
+            .get(&url)
+            .query(&request)
+            .header("X-OperationId", &operation_id)
+            .timeout(timeout + LONG_POLL_GRACE)
+            .send()
+            .await?;
+
+        // Nothing changed while the request was held
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED
+            || response.status() == reqwest::StatusCode::NO_CONTENT
+        {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(Self::error_response(response).await);
+        }
+
+        let mcs_response: McsResponse = response.json().await?;
+
+        Ok(Some(ProviderResponse {
+            settings: mcs_response.settings,
+            deleted: mcs_response.deleted,
+            version: mcs_response.version,
+        }))
+    }
+
     fn default_priority(&self) -> i64 {
         0 // MCS settings have their own priority
     }
@@ -101,6 +171,7 @@ mod tests {
             version: "42".to_string(),
             application: Some("my-app".to_string()),
             mcs_run_env: Some("PROD".to_string()),
+            timeout: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains(r#""runtime":"rust""#));
@@ -114,10 +185,12 @@ mod tests {
             version: "1".to_string(),
             application: None,
             mcs_run_env: None,
+            timeout: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(!json.contains("application"));
         assert!(!json.contains("mcs_run_env"));
+        assert!(!json.contains("timeout"));
     }
 
     #[test]
@@ -135,4 +208,13 @@ mod tests {
         );
         assert_eq!(provider.default_priority(), 0);
     }
+
+    #[test]
+    fn test_mcs_provider_supports_push_only_with_long_poll() {
+        let provider = McsProvider::new("http://test.local".to_string(), "app".to_string(), None);
+        assert!(!provider.supports_push());
+
+        let provider = provider.with_long_poll(Duration::from_secs(30));
+        assert!(provider.supports_push());
+    }
 }
@@ -0,0 +1,241 @@
+// lib/runtime-settings/src/providers/mcs_cache.rs
+//! On-disk last-known-good cache of MCS settings.
+//!
+//! MCS answers with incremental deltas, so the cache keeps the accumulated
+//! settings in memory, applies each successful response on top of them and
+//! rewrites the whole file. The file is replaced atomically (write to a temp
+//! file, then rename), so a crash mid-write never leaves a truncated cache.
+
+use super::ProviderResponse;
+use crate::entities::McsResponse;
+use crate::error::SettingsError;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+pub struct McsCache {
+    path: PathBuf,
+    snapshot: Mutex<Option<McsResponse>>,
+}
+
+impl McsCache {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            snapshot: Mutex::new(None),
+        }
+    }
+
+    /// Path of the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the cached MCS settings from disk.
+    ///
+    /// The loaded response also seeds the in-memory snapshot, so following
+    /// deltas are applied on top of it.
+    pub async fn load(&self) -> Result<ProviderResponse, SettingsError> {
+        let content = tokio::fs::read_to_string(&self.path).await?;
+        let cached: McsResponse = serde_json::from_str(&content)?;
+
+        let response = ProviderResponse {
+            settings: cached.settings.clone(),
+            deleted: vec![],
+            version: cached.version.clone(),
+        };
+        *self.snapshot.lock().await = Some(cached);
+
+        Ok(response)
+    }
+
+    /// Apply a successful MCS response to the snapshot and rewrite the cache file
+    pub async fn store(&self, response: &ProviderResponse) -> Result<(), SettingsError> {
+        let mut snapshot = self.snapshot.lock().await;
+        let cached = snapshot.get_or_insert_with(|| McsResponse {
+            settings: vec![],
+            deleted: vec![],
+            version: String::new(),
+        });
+
+        let unchanged = response.settings.is_empty()
+            && response.deleted.is_empty()
+            && (response.version.is_empty() || response.version == cached.version);
+        if unchanged && self.path.exists() {
+            return Ok(());
+        }
+
+        for deleted in &response.deleted {
+            cached
+                .settings
+                .retain(|s| !(s.key == deleted.key && s.priority == deleted.priority));
+        }
+        for setting in &response.settings {
+            cached
+                .settings
+                .retain(|s| !(s.key == setting.key && s.priority == setting.priority));
+            cached.settings.push(setting.clone());
+        }
+        if !response.version.is_empty() {
+            cached.version = response.version.clone();
+        }
+
+        let content = serde_json::to_vec(&*cached)?;
+        write_atomically(&self.path, &content).await
+    }
+}
+
+/// Write to a sibling temp file and rename it over the target
+async fn write_atomically(path: &Path, content: &[u8]) -> Result<(), SettingsError> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    tokio::fs::write(&tmp_path, content).await?;
+    if let Err(e) = tokio::fs::rename(&tmp_path, path).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{RawSetting, SettingKey};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn raw(key: &str, priority: i64, value: serde_json::Value) -> RawSetting {
+        RawSetting {
+            key: key.to_string(),
+            priority,
+            filter: HashMap::new(),
+            value,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_and_load_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mcs-cache.json");
+
+        let cache = McsCache::new(path.clone());
+        cache
+            .store(&ProviderResponse {
+                settings: vec![raw("KEY", 100, serde_json::json!("value"))],
+                deleted: vec![],
+                version: "42".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let loaded = McsCache::new(path).load().await.unwrap();
+        assert_eq!(loaded.version, "42");
+        assert_eq!(loaded.settings.len(), 1);
+        assert_eq!(loaded.settings[0].key, "KEY");
+        assert_eq!(loaded.settings[0].value, serde_json::json!("value"));
+    }
+
+    #[tokio::test]
+    async fn test_store_applies_deltas() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mcs-cache.json");
+
+        let cache = McsCache::new(path.clone());
+        cache
+            .store(&ProviderResponse {
+                settings: vec![
+                    raw("KEEP", 100, serde_json::json!(1)),
+                    raw("UPDATE", 100, serde_json::json!("old")),
+                    raw("DELETE", 100, serde_json::json!(true)),
+                ],
+                deleted: vec![],
+                version: "1".to_string(),
+            })
+            .await
+            .unwrap();
+        cache
+            .store(&ProviderResponse {
+                settings: vec![raw("UPDATE", 100, serde_json::json!("new"))],
+                deleted: vec![SettingKey {
+                    key: "DELETE".to_string(),
+                    priority: 100,
+                }],
+                version: "2".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let loaded = McsCache::new(path).load().await.unwrap();
+        assert_eq!(loaded.version, "2");
+        let values: HashMap<_, _> = loaded
+            .settings
+            .iter()
+            .map(|s| (s.key.as_str(), s.value.clone()))
+            .collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["KEEP"], serde_json::json!(1));
+        assert_eq!(values["UPDATE"], serde_json::json!("new"));
+    }
+
+    #[tokio::test]
+    async fn test_store_leaves_no_temp_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mcs-cache.json");
+
+        let cache = McsCache::new(path.clone());
+        cache
+            .store(&ProviderResponse {
+                settings: vec![raw("KEY", 1, serde_json::json!(1))],
+                deleted: vec![],
+                version: "1".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let entries: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("mcs-cache.json")]);
+    }
+
+    #[tokio::test]
+    async fn test_load_continues_from_cached_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mcs-cache.json");
+
+        McsCache::new(path.clone())
+            .store(&ProviderResponse {
+                settings: vec![raw("CACHED", 1, serde_json::json!(1))],
+                deleted: vec![],
+                version: "1".to_string(),
+            })
+            .await
+            .unwrap();
+
+        // A new process loads the cache and then receives a delta
+        let cache = McsCache::new(path.clone());
+        cache.load().await.unwrap();
+        cache
+            .store(&ProviderResponse {
+                settings: vec![raw("NEW", 1, serde_json::json!(2))],
+                deleted: vec![],
+                version: "2".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let loaded = McsCache::new(path).load().await.unwrap();
+        assert_eq!(loaded.settings.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_file() {
+        let cache = McsCache::new("/nonexistent/mcs-cache.json".into());
+        assert!(matches!(
+            cache.load().await,
+            Err(SettingsError::FileRead(_))
+        ));
+    }
+}
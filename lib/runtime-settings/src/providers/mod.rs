@@ -2,6 +2,7 @@
 pub mod env;
 pub mod file;
 pub mod mcs;
+pub mod mcs_cache;
 
 use crate::entities::{RawSetting, SettingKey};
 use crate::error::SettingsError;
@@ -26,8 +27,24 @@ pub trait SettingsProvider: Send + Sync {
 
     /// Provider name for logging
     fn name(&self) -> &'static str;
+
+    /// Whether the provider can push changes through `wait_for_changes()`
+    fn supports_push(&self) -> bool {
+        false
+    }
+
+    /// Wait until settings change after `current_version` and return the delta.
+    ///
+    /// Returns `None` if nothing changed before the provider gave up waiting.
+    async fn wait_for_changes(
+        &self,
+        _current_version: &str,
+    ) -> Result<Option<ProviderResponse>, SettingsError> {
+        Ok(None)
+    }
 }
 
 pub use env::EnvProvider;
 pub use file::FileProvider;
 pub use mcs::McsProvider;
+pub use mcs_cache::McsCache;
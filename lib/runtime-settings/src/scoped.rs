@@ -5,12 +5,15 @@
 //! automatically available to code within a scope without explicit passing.
 
 use crate::context::{CustomContext, Request};
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 tokio::task_local! {
     static TASK_REQUEST: Option<Request>;
     static TASK_CUSTOM: CustomContext;
+    static TASK_MEMO: RequestMemo;
 }
 
 thread_local! {
@@ -38,6 +41,87 @@ pub fn current_custom() -> CustomContext {
         .unwrap_or_else(|| THREAD_CUSTOM.with(|c| c.borrow().clone()))
 }
 
+/// Resolved settings memoized for one request scope.
+///
+/// Keyed by settings instance and value type, then by setting key, so lookups
+/// don't allocate. Misses are memoized too. Thread-local custom layers set
+/// inside the scope push a fresh memo layer and restore the outer one on drop.
+struct RequestMemo {
+    layers: RefCell<Vec<MemoLayer>>,
+}
+
+type MemoValue = Arc<dyn Any + Send + Sync>;
+type MemoByKey = HashMap<String, Option<MemoValue>>;
+type MemoLayer = HashMap<(u64, TypeId), MemoByKey>;
+
+impl Default for RequestMemo {
+    fn default() -> Self {
+        Self {
+            layers: RefCell::new(vec![MemoLayer::new()]),
+        }
+    }
+}
+
+impl RequestMemo {
+    fn get<T>(&self, instance: u64, key: &str) -> Option<Option<Arc<T>>>
+    where
+        T: Send + Sync + 'static,
+    {
+        let layers = self.layers.borrow();
+        let values = layers.last()?;
+        let value = values.get(&(instance, TypeId::of::<T>()))?.get(key)?;
+        Some(value.clone().and_then(|v| v.downcast::<T>().ok()))
+    }
+
+    fn insert<T>(&self, instance: u64, key: &str, value: Option<Arc<T>>)
+    where
+        T: Send + Sync + 'static,
+    {
+        if let Some(values) = self.layers.borrow_mut().last_mut() {
+            values
+                .entry((instance, TypeId::of::<T>()))
+                .or_default()
+                .insert(key.to_string(), value.map(|v| v as MemoValue));
+        }
+    }
+
+    fn push_layer(&self) {
+        self.layers.borrow_mut().push(MemoLayer::new());
+    }
+
+    /// Drop the innermost layer. The base layer is only cleared, since a guard
+    /// created outside the scope may be dropped inside it.
+    fn pop_layer(&self) {
+        let mut layers = self.layers.borrow_mut();
+        if layers.len() > 1 {
+            layers.pop();
+        } else if let Some(values) = layers.last_mut() {
+            values.clear();
+        }
+    }
+}
+
+/// Resolve a setting through the request-scoped memo.
+///
+/// Inside `with_task_request` the first lookup of `(instance, key, T)` runs
+/// `resolve` and later ones return the same result for the rest of the scope.
+/// Outside of it `resolve` runs every time.
+pub(crate) fn memoized<T, F>(instance: u64, key: &str, resolve: F) -> Option<Arc<T>>
+where
+    T: Send + Sync + 'static,
+    F: FnOnce() -> Option<Arc<T>>,
+{
+    match TASK_MEMO.try_with(|memo| memo.get::<T>(instance, key)) {
+        Ok(Some(value)) => value,
+        Ok(None) => {
+            let value = resolve();
+            TASK_MEMO.with(|memo| memo.insert(instance, key, value.clone()));
+            value
+        }
+        Err(_) => resolve(),
+    }
+}
+
 /// Guard that restores previous request on drop
 #[must_use = "guard must be held for the request to remain active"]
 pub struct RequestGuard {
@@ -59,11 +143,13 @@ pub struct CustomContextGuard;
 impl Drop for CustomContextGuard {
     fn drop(&mut self) {
         THREAD_CUSTOM.with(|c| c.borrow_mut().pop_layer());
+        let _ = TASK_MEMO.try_with(RequestMemo::pop_layer);
     }
 }
 
 /// Set thread-local request, returns guard that restores previous on drop
-pub fn set_thread_request(req: Request) -> RequestGuard {
+pub fn set_thread_request(mut req: Request) -> RequestGuard {
+    req.normalize_headers();
     let previous = THREAD_REQUEST.with(|r| r.borrow_mut().replace(req));
     RequestGuard { previous }
 }
@@ -71,25 +157,41 @@ pub fn set_thread_request(req: Request) -> RequestGuard {
 /// Add layer to thread-local custom context, returns guard that pops on drop
 pub fn set_thread_custom(layer: HashMap<String, String>) -> CustomContextGuard {
     THREAD_CUSTOM.with(|c| c.borrow_mut().push_layer(layer));
+    let _ = TASK_MEMO.try_with(RequestMemo::push_layer);
     CustomContextGuard
 }
 
-/// Execute async closure with task-local request
-pub async fn with_task_request<F, T>(req: Request, f: F) -> T
+/// Execute async closure with task-local request.
+///
+/// Settings resolved with `get()` inside the scope are memoized per key and
+/// type, so repeated lookups return the same value for the whole request.
+pub async fn with_task_request<F, T>(mut req: Request, f: F) -> T
 where
     F: std::future::Future<Output = T>,
 {
-    TASK_REQUEST.scope(Some(req), f).await
+    req.normalize_headers();
+    TASK_REQUEST
+        .scope(Some(req), TASK_MEMO.scope(RequestMemo::default(), f))
+        .await
 }
 
-/// Execute async closure with additional custom context layer
+/// Execute async closure with additional custom context layer.
+///
+/// Inside a request scope this starts a fresh memo, since results may depend
+/// on the new layer.
 pub async fn with_task_custom<F, T>(layer: HashMap<String, String>, f: F) -> T
 where
     F: std::future::Future<Output = T>,
 {
     let mut ctx = current_custom();
     ctx.push_layer(layer);
-    TASK_CUSTOM.scope(ctx, f).await
+    if TASK_MEMO.try_with(|_| ()).is_ok() {
+        TASK_CUSTOM
+            .scope(ctx, TASK_MEMO.scope(RequestMemo::default(), f))
+            .await
+    } else {
+        TASK_CUSTOM.scope(ctx, f).await
+    }
 }
 
 #[cfg(test)]
@@ -102,6 +204,7 @@ mod tests {
             method: "POST".to_string(),
             path: "/api".to_string(),
             headers: std::collections::HashMap::new(),
+            ..Default::default()
         };
 
         {
@@ -113,6 +216,23 @@ mod tests {
         assert!(current_request().is_none());
     }
 
+    #[tokio::test]
+    async fn test_scoped_request_headers_normalized() {
+        let req = Request {
+            headers: [("X-Real-Email".to_string(), "a@b.com".to_string())].into(),
+            ..Default::default()
+        };
+
+        {
+            let _guard = set_thread_request(req.clone());
+            let current = current_request().unwrap();
+            assert!(current.headers.contains_key("x-real-email"));
+        }
+
+        let current = with_task_request(req, async { current_request() }).await;
+        assert!(current.unwrap().headers.contains_key("x-real-email"));
+    }
+
     #[test]
     fn test_thread_local_custom() {
         let layer: HashMap<String, String> = [("key".to_string(), "value".to_string())].into();
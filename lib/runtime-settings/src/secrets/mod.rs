@@ -1,6 +1,8 @@
 // lib/runtime-settings/src/secrets/mod.rs
 
+pub mod redact;
 pub mod resolver;
+pub mod template;
 
 use crate::error::SettingsError;
 
@@ -12,22 +14,79 @@ pub enum JsonPathKey {
 }
 
 /// Information about a single secret usage in a setting value
-#[derive(Debug, Clone)]
-pub struct SecretUsage {
-    /// Full Vault path including mount and `/data/`: "secret/data/db/creds"
-    pub path: String,
-    /// Key within the secret: "password"
-    pub key: String,
-    /// Where to substitute in JSON: ["connection", "password"]
-    pub value_path: Vec<JsonPathKey>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretUsage {
+    /// `{"$secret": "path:key"}`, replaced by the secret value
+    Value {
+        /// Full Vault path: "secret/data/db/creds" for KV v2 (with `/data/`),
+        /// "database/creds/app" for other engines
+        path: String,
+        /// Key within the secret: "password"
+        key: String,
+        /// Pinned KV v2 version from a `path:key@5` reference, latest if `None`
+        version: Option<u64>,
+        /// Where to substitute in JSON: ["connection", "password"]
+        value_path: Vec<JsonPathKey>,
+    },
+    /// String with `${secret:path:key}` placeholders, replaced by the
+    /// rendered string
+    Template {
+        parts: Vec<TemplatePart>,
+        /// Where to substitute in JSON: ["connection", "dsn"]
+        value_path: Vec<JsonPathKey>,
+    },
 }
-use std::collections::HashMap;
+
+impl SecretUsage {
+    /// Where the resolved value goes in the setting value
+    pub fn value_path(&self) -> &[JsonPathKey] {
+        match self {
+            Self::Value { value_path, .. } | Self::Template { value_path, .. } => value_path,
+        }
+    }
+
+    /// Every `(path, key, version)` secret this usage refers to
+    pub fn references(&self) -> Vec<(&str, &str, Option<u64>)> {
+        match self {
+            Self::Value {
+                path, key, version, ..
+            } => vec![(path.as_str(), key.as_str(), *version)],
+            Self::Template { parts, .. } => parts
+                .iter()
+                .filter_map(|part| match part {
+                    TemplatePart::Secret { path, key, version } => {
+                        Some((path.as_str(), key.as_str(), *version))
+                    }
+                    TemplatePart::Literal(_) => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What `RuntimeSettings::init()` does with secrets referenced by settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SecretsPrefetch {
+    /// Fetch each secret on first use
+    #[default]
+    Lazy,
+    /// Fetch every referenced secret at init, any failure fails init
+    FailFast,
+    /// Fetch every referenced secret at init, failures are logged and
+    /// retried on first use
+    Warn,
+}
+use arc_swap::ArcSwap;
+use futures::StreamExt;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
-use vault_client::VaultClient;
+use vault_client::{LeaseInfo, VaultClient};
 
+pub use redact::{REDACTED, Secret, redact_secrets};
 pub use resolver::{resolve_secrets, resolve_secrets_sync};
+pub use template::TemplatePart;
 
 /// Parse secret usages from a JSON value during Setting compilation
 pub fn find_secret_usages(value: &serde_json::Value) -> Result<Vec<SecretUsage>, SettingsError> {
@@ -46,10 +105,11 @@ fn find_secrets_recursive(
             // Check for {"$secret": "path:key"}
             if map.len() == 1 {
                 if let Some(serde_json::Value::String(reference)) = map.get("$secret") {
-                    let (path, key) = parse_secret_ref(reference)?;
-                    usages.push(SecretUsage {
+                    let (path, key, version) = parse_secret_ref(reference)?;
+                    usages.push(SecretUsage::Value {
                         path,
                         key,
+                        version,
                         value_path: current_path.clone(),
                     });
                     return Ok(());
@@ -70,58 +130,158 @@ fn find_secrets_recursive(
                 current_path.pop();
             }
         }
+        serde_json::Value::String(text) => {
+            // Check for "...${secret:path:key}..."
+            if let Some(parts) = template::parse_template(text)? {
+                usages.push(SecretUsage::Template {
+                    parts,
+                    value_path: current_path.clone(),
+                });
+            }
+        }
         _ => {}
     }
     Ok(())
 }
 
-fn parse_secret_ref(reference: &str) -> Result<(String, String), SettingsError> {
-    reference
-        .split_once(':')
-        .map(|(p, k)| (p.to_string(), k.to_string()))
-        .ok_or_else(|| SettingsError::InvalidSecretReference {
-            reference: reference.to_string(),
-        })
+/// Parse `path:key` or `path:key@version` into path, key and pinned version.
+///
+/// A trailing `@` followed by digits pins a KV v2 version; any other `@` is
+/// part of the key.
+pub(crate) fn parse_secret_ref(
+    reference: &str,
+) -> Result<(String, String, Option<u64>), SettingsError> {
+    let invalid = || SettingsError::InvalidSecretReference {
+        reference: reference.to_string(),
+    };
+    let (path, key) = reference.split_once(':').ok_or_else(invalid)?;
+
+    let (key, version) = match key.rsplit_once('@') {
+        Some((key, version))
+            if !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            let version: u64 = version.parse().map_err(|_| invalid())?;
+            // Version 0 means latest to Vault; only KV v2 has versions
+            if version == 0 || is_generic_engine_path(path) {
+                return Err(invalid());
+            }
+            (key, Some(version))
+        }
+        _ => (key, None),
+    };
+    Ok((path.to_string(), key.to_string(), version))
+}
+
+/// Endpoints of engines that issue credentials: `creds` (database, AWS,
+/// Consul, ...), `static-creds` (database static roles) and `sts` (AWS)
+const GENERIC_ENGINE_ENDPOINTS: &[&str] = &["creds", "static-creds", "sts"];
+
+/// Whether a path is `<mount>/<endpoint>/<role>` for a credentials engine and
+/// is read with a plain GET. Any other path, including KV v2 mounts with a
+/// nested name, is read as KV v2.
+fn is_generic_engine_path(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').collect();
+    match segments.as_slice() {
+        [mount @ .., endpoint, role] => {
+            !mount.is_empty()
+                && !mount.contains(&"data")
+                && !role.is_empty()
+                && GENERIC_ENGINE_ENDPOINTS.contains(endpoint)
+        }
+        _ => false,
+    }
 }
 
 /// Cached secret with metadata
+#[derive(Clone)]
 struct CachedSecret {
     value: serde_json::Value,
-    #[allow(dead_code)]
     lease_id: Option<String>,
     lease_duration: Option<Duration>,
     renewable: bool,
+    /// When the value was read or the lease last renewed
     fetched_at: Instant,
 }
 
 impl CachedSecret {
+    fn new(
+        data: HashMap<String, serde_json::Value>,
+        lease: &LeaseInfo,
+    ) -> Result<Self, SettingsError> {
+        let value = serde_json::to_value(data).map_err(|e| SettingsError::Vault(e.to_string()))?;
+        let mut cached = Self {
+            value,
+            lease_id: None,
+            lease_duration: None,
+            renewable: false,
+            fetched_at: Instant::now(),
+        };
+        cached.set_lease(lease);
+        Ok(cached)
+    }
+
+    fn set_lease(&mut self, lease: &LeaseInfo) {
+        self.lease_id = Some(lease.lease_id.clone()).filter(|id| !id.is_empty());
+        self.lease_duration =
+            Some(Duration::from_secs(lease.lease_duration)).filter(|d| !d.is_zero());
+        self.renewable = lease.renewable;
+        self.fetched_at = Instant::now();
+    }
+
+    /// True once `threshold` of the lease TTL has elapsed
     fn needs_refresh(&self, threshold: f64) -> bool {
         match self.lease_duration {
-            Some(duration) if self.renewable => {
+            Some(duration) => {
                 let elapsed = self.fetched_at.elapsed();
                 let threshold_duration =
                     Duration::from_secs_f64(duration.as_secs_f64() * threshold);
                 elapsed >= threshold_duration
             }
-            _ => false,
+            None => false,
         }
     }
+
+    /// Lease ID if the lease can be renewed instead of re-reading the secret
+    fn renewable_lease(&self) -> Option<&str> {
+        self.lease_id.as_deref().filter(|_| self.renewable)
+    }
 }
 
+type LoadLock = Arc<tokio::sync::Mutex<()>>;
+
 pub struct SecretsService {
     client: Option<VaultClient>,
-    cache: RwLock<HashMap<String, CachedSecret>>,
+    /// Cached secrets by path. Reads never block: writers publish an updated
+    /// copy under `write_lock`
+    cache: ArcSwap<HashMap<String, Arc<CachedSecret>>>,
+    /// Pinned KV versions by `(path, version)`; they never change, so they
+    /// are not refreshed
+    pinned: ArcSwap<HashMap<(String, u64), serde_json::Value>>,
+    /// Serializes updates of `cache` and `pinned`
+    write_lock: Mutex<()>,
+    /// One lock per `(path, version)` being loaded, so concurrent misses share
+    /// a single Vault read (and a single credential pair for dynamic engines)
+    loading: Mutex<HashMap<(String, Option<u64>), LoadLock>>,
     refresh_intervals: HashMap<String, Duration>,
+    /// How often `refresh` runs; renewed leases shorter than this are re-read
+    refresh_interval: Duration,
     version: AtomicU64,
 }
 
+/// Refresh interval assumed until `with_refresh_interval` is called
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
 impl SecretsService {
     /// Create without Vault (secrets will fail)
     pub fn new_without_vault() -> Self {
         Self {
             client: None,
-            cache: RwLock::new(HashMap::new()),
+            cache: ArcSwap::from_pointee(HashMap::new()),
+            pinned: ArcSwap::from_pointee(HashMap::new()),
+            write_lock: Mutex::new(()),
+            loading: Mutex::new(HashMap::new()),
             refresh_intervals: Self::load_refresh_intervals(),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
             version: AtomicU64::new(0),
         }
     }
@@ -130,12 +290,23 @@ impl SecretsService {
     pub fn new(client: VaultClient) -> Self {
         Self {
             client: Some(client),
-            cache: RwLock::new(HashMap::new()),
+            cache: ArcSwap::from_pointee(HashMap::new()),
+            pinned: ArcSwap::from_pointee(HashMap::new()),
+            write_lock: Mutex::new(()),
+            loading: Mutex::new(HashMap::new()),
             refresh_intervals: Self::load_refresh_intervals(),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
             version: AtomicU64::new(0),
         }
     }
 
+    /// Set how often `refresh` is called, so leases are rotated before they
+    /// expire between two refreshes
+    pub fn with_refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
     /// Get current version (for cache invalidation in Settings)
     pub fn version(&self) -> u64 {
         self.version.load(Ordering::Acquire)
@@ -169,14 +340,36 @@ impl SecretsService {
 
     /// Get secret value by path and key
     pub async fn get(&self, path: &str, key: &str) -> Result<serde_json::Value, SettingsError> {
+        self.get_at(path, key, None).await
+    }
+
+    /// Get secret value by path and key, from a pinned KV version if given
+    pub async fn get_at(
+        &self,
+        path: &str,
+        key: &str,
+        version: Option<u64>,
+    ) -> Result<serde_json::Value, SettingsError> {
         let client = self
             .client
             .as_ref()
             .ok_or(SettingsError::SecretWithoutVault)?;
 
-        // Check cache first
-        {
-            let cache = self.cache.read().await;
+        // Check cache first. Pinned versions never change, so a cached one
+        // without the key is final.
+        if let Some(version) = version {
+            let pinned = self.pinned.load();
+            if let Some(value) = pinned.get(&(path.to_string(), version)) {
+                return value
+                    .get(key)
+                    .cloned()
+                    .ok_or_else(|| SettingsError::SecretKeyNotFound {
+                        path: path.to_string(),
+                        key: key.to_string(),
+                    });
+            }
+        } else {
+            let cache = self.cache.load();
             if let Some(cached) = cache.get(path) {
                 if let Some(value) = cached.value.get(key) {
                     return Ok(value.clone());
@@ -184,32 +377,8 @@ impl SecretsService {
             }
         }
 
-        // Fetch from Vault using vault-client
-        let kv_data = client
-            .kv_read_raw(path)
-            .await
-            .map_err(|e| SettingsError::Vault(e.to_string()))?;
-
-        // Convert HashMap to Value for caching
-        let secret: serde_json::Value = serde_json::to_value(&kv_data.data)
-            .map_err(|e| SettingsError::Vault(e.to_string()))?;
-
-        // Cache it
-        {
-            let mut cache = self.cache.write().await;
-            cache.insert(
-                path.to_string(),
-                CachedSecret {
-                    value: secret.clone(),
-                    lease_id: None,
-                    lease_duration: None,
-                    renewable: false,
-                    fetched_at: Instant::now(),
-                },
-            );
-        }
-
-        secret
+        self.load(client, path, version, Some(key))
+            .await?
             .get(key)
             .cloned()
             .ok_or_else(|| SettingsError::SecretKeyNotFound {
@@ -218,34 +387,200 @@ impl SecretsService {
             })
     }
 
+    /// Fetch a secret from Vault and cache it along with its lease.
+    ///
+    /// Loads of the same path are serialized. A caller that waited for another
+    /// load reuses its result if it has `key` (or any result without a key,
+    /// or a pinned version).
+    async fn load(
+        &self,
+        client: &VaultClient,
+        path: &str,
+        version: Option<u64>,
+        key: Option<&str>,
+    ) -> Result<serde_json::Value, SettingsError> {
+        let lock = self
+            .loading
+            .lock()
+            .unwrap()
+            .entry((path.to_string(), version))
+            .or_default()
+            .clone();
+
+        let result = {
+            let _guard = lock.lock().await;
+            let cached = self.cached_secret(path, version).filter(|secret| {
+                version.is_some() || key.is_none_or(|key| secret.get(key).is_some())
+            });
+            match cached {
+                Some(secret) => Ok(secret),
+                None => self.fetch_and_cache(client, path, version).await,
+            }
+        };
+
+        // Drop the lock entry unless another load is waiting on it
+        let mut loading = self.loading.lock().unwrap();
+        if Arc::strong_count(&lock) == 2 {
+            loading.remove(&(path.to_string(), version));
+        }
+        result
+    }
+
+    /// Whole cached secret at `path`, from the pinned cache if `version` is set
+    fn cached_secret(&self, path: &str, version: Option<u64>) -> Option<serde_json::Value> {
+        match version {
+            Some(version) => self
+                .pinned
+                .load()
+                .get(&(path.to_string(), version))
+                .cloned(),
+            None => self
+                .cache
+                .load()
+                .get(path)
+                .map(|cached| cached.value.clone()),
+        }
+    }
+
+    /// Apply `update` to a copy of the cache and publish it
+    fn update_cache<R>(
+        &self,
+        update: impl FnOnce(&mut HashMap<String, Arc<CachedSecret>>) -> R,
+    ) -> R {
+        let _write_guard = self.write_lock.lock().unwrap();
+        let mut cache = HashMap::clone(&self.cache.load());
+        let result = update(&mut cache);
+        self.cache.store(Arc::new(cache));
+        result
+    }
+
+    /// Read a secret from Vault and store it in the cache
+    async fn fetch_and_cache(
+        &self,
+        client: &VaultClient,
+        path: &str,
+        version: Option<u64>,
+    ) -> Result<serde_json::Value, SettingsError> {
+        if let Some(version) = version {
+            let kv_data = client
+                .kv_read_version(path, version)
+                .await
+                .map_err(|e| SettingsError::Vault(e.to_string()))?;
+            let secret = serde_json::to_value(kv_data.data)
+                .map_err(|e| SettingsError::Vault(e.to_string()))?;
+            let _write_guard = self.write_lock.lock().unwrap();
+            let mut pinned = HashMap::clone(&self.pinned.load());
+            pinned.insert((path.to_string(), version), secret.clone());
+            self.pinned.store(Arc::new(pinned));
+            return Ok(secret);
+        }
+
+        let cached = Self::fetch(client, path).await?;
+        let secret = cached.value.clone();
+        self.update_cache(|cache| cache.insert(path.to_string(), Arc::new(cached)));
+        Ok(secret)
+    }
+
+    /// Fetch the given `(path, key, version)` secrets into the cache, reading
+    /// up to `concurrency` distinct paths at once.
+    ///
+    /// Returns one error per path that failed to load or key that is missing.
+    pub async fn prefetch<'a>(
+        &self,
+        references: impl IntoIterator<Item = (&'a str, &'a str, Option<u64>)>,
+        concurrency: usize,
+    ) -> Vec<SettingsError> {
+        let mut keys_by_path: BTreeMap<(&str, Option<u64>), Vec<&str>> = BTreeMap::new();
+        for (path, key, version) in references {
+            keys_by_path.entry((path, version)).or_default().push(key);
+        }
+        if keys_by_path.is_empty() {
+            return Vec::new();
+        }
+        let Some(client) = &self.client else {
+            return vec![SettingsError::SecretWithoutVault];
+        };
+
+        futures::stream::iter(keys_by_path)
+            .map(|((path, version), keys)| async move {
+                let secret = match self.load(client, path, version, None).await {
+                    Ok(secret) => secret,
+                    Err(e) => return vec![e],
+                };
+                keys.into_iter()
+                    .filter(|key| secret.get(key).is_none())
+                    .map(|key| SettingsError::SecretKeyNotFound {
+                        path: path.to_string(),
+                        key: key.to_string(),
+                    })
+                    .collect()
+            })
+            .buffer_unordered(concurrency.max(1))
+            .flat_map(futures::stream::iter)
+            .collect()
+            .await
+    }
+
     /// Synchronous get for use in RuntimeSettings::get()
     ///
-    /// Uses block_in_place to fetch from Vault if not cached.
-    /// Only works in multi-threaded tokio runtime.
+    /// Cached secrets are read without blocking. Uses block_in_place to fetch
+    /// from Vault if not cached, which only works in multi-threaded tokio runtime.
     pub fn get_sync(&self, path: &str, key: &str) -> Result<serde_json::Value, SettingsError> {
-        // Fast path: check cache with blocking read
-        {
-            let cache = self.cache.blocking_read();
-            if let Some(cached) = cache.get(path) {
-                if let Some(value) = cached.value.get(key) {
-                    return Ok(value.clone());
-                }
-                // Path exists but key not found
-                return Err(SettingsError::SecretKeyNotFound {
-                    path: path.to_string(),
-                    key: key.to_string(),
-                });
-            }
+        self.get_sync_at(path, key, None)
+    }
+
+    /// Synchronous `get_at`, see `get_sync`
+    pub fn get_sync_at(
+        &self,
+        path: &str,
+        key: &str,
+        version: Option<u64>,
+    ) -> Result<serde_json::Value, SettingsError> {
+        // Fast path: cached secrets are read without waiting for writers
+        let cached = match version {
+            Some(version) => self
+                .pinned
+                .load()
+                .get(&(path.to_string(), version))
+                .map(|v| v.get(key).cloned()),
+            None => self
+                .cache
+                .load()
+                .get(path)
+                .map(|c| c.value.get(key).cloned()),
+        };
+        if let Some(value) = cached {
+            // Path exists but key not found
+            return value.ok_or_else(|| SettingsError::SecretKeyNotFound {
+                path: path.to_string(),
+                key: key.to_string(),
+            });
         }
 
         // Slow path: fetch from Vault using block_in_place
         tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(self.get(path, key))
+            tokio::runtime::Handle::current().block_on(self.get_at(path, key, version))
         })
     }
 
+    /// Read a secret from Vault. Credentials engine paths such as
+    /// `database/creds/<role>` are read as is, anything else as KV v2.
+    async fn fetch(client: &VaultClient, path: &str) -> Result<CachedSecret, SettingsError> {
+        let (data, lease) = if is_generic_engine_path(path) {
+            let secret = client.read(path).await;
+            secret.map(|s| (s.data, s.lease))
+        } else {
+            let kv_data = client.kv_read_raw(path).await;
+            kv_data.map(|kv| (kv.data, kv.lease))
+        }
+        .map_err(|e| SettingsError::Vault(e.to_string()))?;
+
+        CachedSecret::new(data, &lease)
+    }
+
     fn needs_static_refresh(&self, path: &str, cached: &CachedSecret) -> bool {
-        if cached.renewable {
+        // Leased secrets are refreshed by their lease
+        if cached.lease_duration.is_some() {
             return false;
         }
         for (pattern, interval) in &self.refresh_intervals {
@@ -263,30 +598,37 @@ impl SecretsService {
             None => return Ok(()),
         };
 
-        let paths_to_refresh: Vec<String> = {
-            let cache = self.cache.read().await;
+        // (path, lease to renew) for every secret due for refresh
+        let paths_to_refresh: Vec<(String, Option<String>)> = {
+            let cache = self.cache.load();
             cache
                 .iter()
                 .filter(|(path, cached)| {
                     cached.needs_refresh(0.75) || self.needs_static_refresh(path, cached)
                 })
-                .map(|(path, _)| path.clone())
+                .map(|(path, cached)| (path.clone(), cached.renewable_lease().map(String::from)))
                 .collect()
         };
 
         let mut any_changed = false;
 
-        for path in paths_to_refresh {
-            match client.kv_read_raw(&path).await {
-                Ok(kv_data) => {
-                    let new_value: serde_json::Value = match serde_json::to_value(&kv_data.data) {
-                        Ok(v) => v,
-                        Err(e) => {
-                            tracing::warn!(path = %path, error = %e, "Failed to convert secret data");
-                            continue;
-                        }
-                    };
-                    let changed = self.update_cached_secret(&path, new_value).await;
+        for (path, lease_id) in paths_to_refresh {
+            // Renew the lease while Vault allows it, the value stays the same
+            if let Some(lease_id) = lease_id {
+                match self.renew_lease(client, &path, &lease_id).await {
+                    Ok(true) => continue,
+                    Ok(false) => {
+                        tracing::info!(path = %path, "Lease TTL capped, re-reading secret");
+                    }
+                    Err(e) => {
+                        tracing::info!(path = %path, error = %e, "Lease renewal failed, re-reading secret");
+                    }
+                }
+            }
+
+            match Self::fetch(client, &path).await {
+                Ok(cached) => {
+                    let changed = self.update_cached_secret(&path, cached);
                     if changed {
                         any_changed = true;
                         tracing::debug!(path = %path, "Secret value changed");
@@ -307,27 +649,52 @@ impl SecretsService {
         Ok(())
     }
 
+    /// Renew the lease of a cached secret and restart its TTL.
+    ///
+    /// Returns `false` when the lease is close to its max TTL: Vault granted
+    /// less than the previous TTL or less than one refresh interval. The
+    /// renewed lease is kept, but the secret should be re-read.
+    async fn renew_lease(
+        &self,
+        client: &VaultClient,
+        path: &str,
+        lease_id: &str,
+    ) -> Result<bool, SettingsError> {
+        let lease = client
+            .renew_lease(lease_id, None)
+            .await
+            .map_err(|e| SettingsError::Vault(e.to_string()))?;
+        if lease.lease_duration == 0 {
+            return Err(SettingsError::Vault(
+                "lease renewed with zero TTL".to_string(),
+            ));
+        }
+
+        let granted = Duration::from_secs(lease.lease_duration);
+        let previous = self.update_cache(|cache| {
+            let cached = cache.get_mut(path)?;
+            let previous = cached.lease_duration;
+            Arc::make_mut(cached).set_lease(&lease);
+            previous
+        });
+        tracing::debug!(path = %path, ttl = lease.lease_duration, "Secret lease renewed");
+
+        let capped = previous.is_some_and(|previous| granted < previous);
+        Ok(!capped && granted >= self.refresh_interval)
+    }
+
     /// Update cached secret, returns true if value changed
-    async fn update_cached_secret(&self, path: &str, new_value: serde_json::Value) -> bool {
-        let mut cache = self.cache.write().await;
-
-        let changed = cache
-            .get(path)
-            .map(|cached| cached.value != new_value)
-            .unwrap_or(true);
-
-        cache.insert(
-            path.to_string(),
-            CachedSecret {
-                value: new_value,
-                lease_id: None,
-                lease_duration: None,
-                renewable: false,
-                fetched_at: Instant::now(),
-            },
-        );
+    fn update_cached_secret(&self, path: &str, new: CachedSecret) -> bool {
+        self.update_cache(|cache| {
+            let changed = cache
+                .get(path)
+                .map(|cached| cached.value != new.value)
+                .unwrap_or(true);
+
+            cache.insert(path.to_string(), Arc::new(new));
 
-        changed
+            changed
+        })
     }
 }
 
@@ -350,9 +717,15 @@ mod tests {
         });
         let usages = find_secret_usages(&value).unwrap();
         assert_eq!(usages.len(), 1);
-        assert_eq!(usages[0].path, "secret/data/db/creds");
-        assert_eq!(usages[0].key, "password");
-        assert_eq!(usages[0].value_path, vec![JsonPathKey::Field("password".to_string())]);
+        assert_eq!(
+            usages[0],
+            SecretUsage::Value {
+                path: "secret/data/db/creds".to_string(),
+                key: "password".to_string(),
+                version: None,
+                value_path: vec![JsonPathKey::Field("password".to_string())],
+            }
+        );
     }
 
     #[test]
@@ -366,13 +739,19 @@ mod tests {
         });
         let usages = find_secret_usages(&value).unwrap();
         assert_eq!(usages.len(), 1);
-        assert_eq!(usages[0].path, "secret/data/db/creds");
-        assert_eq!(usages[0].key, "password");
-        assert_eq!(usages[0].value_path, vec![
-            JsonPathKey::Field("database".to_string()),
-            JsonPathKey::Field("connection".to_string()),
-            JsonPathKey::Field("password".to_string()),
-        ]);
+        assert_eq!(
+            usages[0],
+            SecretUsage::Value {
+                path: "secret/data/db/creds".to_string(),
+                key: "password".to_string(),
+                version: None,
+                value_path: vec![
+                    JsonPathKey::Field("database".to_string()),
+                    JsonPathKey::Field("connection".to_string()),
+                    JsonPathKey::Field("password".to_string()),
+                ],
+            }
+        );
     }
 
     #[test]
@@ -385,8 +764,12 @@ mod tests {
         });
         let usages = find_secret_usages(&value).unwrap();
         assert_eq!(usages.len(), 2);
-        assert_eq!(usages[0].path, "secret/data/servers/1");
-        assert_eq!(usages[1].path, "secret/data/servers/2");
+        assert!(
+            matches!(&usages[0], SecretUsage::Value { path, .. } if path == "secret/data/servers/1")
+        );
+        assert!(
+            matches!(&usages[1], SecretUsage::Value { path, .. } if path == "secret/data/servers/2")
+        );
     }
 
     #[test]
@@ -395,7 +778,10 @@ mod tests {
             "password": {"$secret": "no-colon-here"}
         });
         let result = find_secret_usages(&value);
-        assert!(matches!(result, Err(SettingsError::InvalidSecretReference { .. })));
+        assert!(matches!(
+            result,
+            Err(SettingsError::InvalidSecretReference { .. })
+        ));
     }
 
     #[test]
@@ -403,7 +789,111 @@ mod tests {
         let value = serde_json::json!({"$secret": "path:key"});
         let usages = find_secret_usages(&value).unwrap();
         assert_eq!(usages.len(), 1);
-        assert!(usages[0].value_path.is_empty());
+        assert!(usages[0].value_path().is_empty());
+    }
+
+    #[test]
+    fn test_find_secret_usages_template() {
+        let value = serde_json::json!({
+            "dsn": "postgres://app:${secret:secret/data/db:password}@db/app",
+            "host": "db",
+            "hint": "use $${secret:path:key} in strings"
+        });
+        // The escaped hint is a plain value, not a template
+        let usages = find_secret_usages(&value).unwrap();
+        assert_eq!(usages.len(), 1);
+
+        let dsn = usages
+            .iter()
+            .find(|u| u.value_path() == [JsonPathKey::Field("dsn".to_string())])
+            .unwrap();
+        let SecretUsage::Template { parts, .. } = dsn else {
+            panic!("expected a template usage");
+        };
+        assert_eq!(
+            parts[1],
+            TemplatePart::Secret {
+                path: "secret/data/db".to_string(),
+                key: "password".to_string(),
+                version: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_secret_usages_invalid_template() {
+        let value = serde_json::json!({"dsn": "postgres://${secret:secret/data/db"});
+        let result = find_secret_usages(&value);
+        assert!(matches!(
+            result,
+            Err(SettingsError::InvalidSecretReference { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_secret_ref_versions() {
+        let parse = |r: &str| parse_secret_ref(r).unwrap();
+        assert_eq!(
+            parse("secret/data/db:password"),
+            ("secret/data/db".to_string(), "password".to_string(), None)
+        );
+        assert_eq!(
+            parse("secret/data/db:password@5"),
+            (
+                "secret/data/db".to_string(),
+                "password".to_string(),
+                Some(5)
+            )
+        );
+        // `@` not followed by a version is part of the key
+        assert_eq!(
+            parse("secret/data/mail:admin@example.com"),
+            (
+                "secret/data/mail".to_string(),
+                "admin@example.com".to_string(),
+                None
+            )
+        );
+        // The version is taken from the key, never from the path
+        assert_eq!(
+            parse("secret/data/users/bob@5:token"),
+            (
+                "secret/data/users/bob@5".to_string(),
+                "token".to_string(),
+                None
+            )
+        );
+
+        for invalid in [
+            "secret/data/db:password@0",
+            "secret/data/db:password@99999999999999999999",
+            "database/creds/app:password@2",
+        ] {
+            assert!(
+                matches!(
+                    parse_secret_ref(invalid),
+                    Err(SettingsError::InvalidSecretReference { .. })
+                ),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_generic_engine_path() {
+        assert!(is_generic_engine_path("database/creds/app"));
+        assert!(is_generic_engine_path("database/creds/data"));
+        assert!(is_generic_engine_path("database/static-creds/app"));
+        assert!(is_generic_engine_path("aws/sts/deploy"));
+        assert!(is_generic_engine_path("team/database/creds/app"));
+
+        // KV v2, including nested mounts and keys under a `creds` folder
+        assert!(!is_generic_engine_path("secret/data/db/creds"));
+        assert!(!is_generic_engine_path("team/kv/data/app"));
+        assert!(!is_generic_engine_path("secret/data/app/creds/x"));
+        assert!(!is_generic_engine_path("kv1/apps/data/x"));
+        assert!(!is_generic_engine_path("creds/app"));
+        assert!(!is_generic_engine_path("database/creds/"));
     }
 
     #[test]
@@ -411,4 +901,23 @@ mod tests {
         let service = SecretsService::new_without_vault();
         assert_eq!(service.version(), 0);
     }
+
+    #[test]
+    fn test_get_sync_while_refresh_holds_write_lock() {
+        let service = SecretsService::new_without_vault();
+        let data = [("password".to_string(), serde_json::json!("p1"))].into();
+        let cached = CachedSecret::new(data, &LeaseInfo::default()).unwrap();
+        service.update_cached_secret("secret/data/db", cached);
+
+        // Outside a runtime the slow path would panic, so this must hit the cache
+        let _write_guard = service.write_lock.lock().unwrap();
+        assert_eq!(
+            service.get_sync("secret/data/db", "password").unwrap(),
+            serde_json::json!("p1")
+        );
+        assert!(matches!(
+            service.get_sync("secret/data/db", "user"),
+            Err(SettingsError::SecretKeyNotFound { .. })
+        ));
+    }
 }
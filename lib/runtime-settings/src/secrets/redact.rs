@@ -0,0 +1,133 @@
+// lib/runtime-settings/src/secrets/redact.rs
+//! Keeping secret values out of logs.
+
+use super::SecretUsage;
+use super::resolver::set_at_path;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroize;
+
+/// Placeholder printed instead of a secret value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Value that never shows up in logs.
+///
+/// Debug, Display and Serialize print `[REDACTED]`; the inner value is only
+/// reachable through `expose_secret()` and is zeroized on drop. Deserializes
+/// like `T`, so it works with `get::<Secret<String>>(key)`.
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Access the secret value
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> std::fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T: Zeroize> std::fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T: Zeroize> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de, T: Zeroize + Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Copy of a setting value with every secret usage replaced by `[REDACTED]`
+pub fn redact_secrets(value: &serde_json::Value, usages: &[SecretUsage]) -> serde_json::Value {
+    let mut redacted = value.clone();
+    for usage in usages {
+        // Paths come from this value, so they always exist
+        let _ = set_at_path(&mut redacted, usage.value_path(), REDACTED.into());
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::find_secret_usages;
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let secret: Secret<String> = serde_json::from_str(r#""hunter2""#).unwrap();
+
+        assert_eq!(secret.expose_secret(), "hunter2");
+        assert_eq!(format!("{:?}", secret), REDACTED);
+        assert_eq!(format!("{}", secret), REDACTED);
+        assert_eq!(serde_json::to_string(&secret).unwrap(), r#""[REDACTED]""#);
+    }
+
+    #[test]
+    fn test_secret_inside_struct() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Db {
+            user: String,
+            password: Secret<String>,
+        }
+
+        let db: Db = serde_json::from_str(r#"{"user": "app", "password": "hunter2"}"#).unwrap();
+        let debug = format!("{:?}", db);
+        assert!(debug.contains("app"));
+        assert!(!debug.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_redact_secrets() {
+        let value = serde_json::json!({
+            "host": "db",
+            "password": {"$secret": "secret/data/db:password"},
+            "dsn": "postgres://app:${secret:secret/data/db:password}@db",
+            "replicas": [{"password": {"$secret": "secret/data/db:replica"}}]
+        });
+        let usages = find_secret_usages(&value).unwrap();
+
+        let redacted = redact_secrets(&value, &usages);
+        assert_eq!(
+            redacted,
+            serde_json::json!({
+                "host": "db",
+                "password": REDACTED,
+                "dsn": REDACTED,
+                "replicas": [{"password": REDACTED}]
+            })
+        );
+    }
+}
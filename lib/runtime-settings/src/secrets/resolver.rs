@@ -1,11 +1,14 @@
 // lib/runtime-settings/src/secrets/resolver.rs
 
-use super::{JsonPathKey, SecretUsage, SecretsService};
+use super::template::{TemplatePart, parse_template, render_template};
+use super::{JsonPathKey, SecretUsage, SecretsService, parse_secret_ref};
 use crate::error::SettingsError;
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 
-/// Recursively resolve {"$secret": "path:key"} references in a JSON value
+/// Recursively resolve {"$secret": "path:key"} references and
+/// "${secret:path:key}" placeholders in a JSON value
 pub fn resolve_secrets<'a>(
     value: &'a serde_json::Value,
     secrets: &'a SecretsService,
@@ -34,27 +37,41 @@ pub fn resolve_secrets<'a>(
                 }
                 Ok(serde_json::Value::Array(result))
             }
+            serde_json::Value::String(text) => match parse_template(text)? {
+                Some(parts) => resolve_template(&parts, secrets).await,
+                None => Ok(value.clone()),
+            },
             _ => Ok(value.clone()),
         }
     })
 }
 
-/// Resolve a single secret reference like "path/to/secret:key"
+/// Resolve a single secret reference like "path/to/secret:key" or
+/// "path/to/secret:key@5"
 async fn resolve_secret_reference(
     reference: &str,
     secrets: &SecretsService,
 ) -> Result<serde_json::Value, SettingsError> {
-    let parts: Vec<&str> = reference.splitn(2, ':').collect();
-    if parts.len() != 2 {
-        return Err(SettingsError::InvalidSecretReference {
-            reference: reference.to_string(),
-        });
-    }
-
-    let path = parts[0];
-    let key = parts[1];
+    let (path, key, version) = parse_secret_ref(reference)?;
+    secrets.get_at(&path, &key, version).await
+}
 
-    secrets.get(path, key).await
+/// Render a string template, fetching its secrets first
+async fn resolve_template(
+    parts: &[TemplatePart],
+    secrets: &SecretsService,
+) -> Result<serde_json::Value, SettingsError> {
+    let mut values = HashMap::new();
+    for part in parts {
+        if let TemplatePart::Secret { path, key, version } = part {
+            let value = secrets.get_at(path, key, *version).await?;
+            values.insert((path.clone(), key.clone(), *version), value);
+        }
+    }
+    let rendered = render_template(parts, |path, key, version| {
+        Ok(values[&(path.to_string(), key.to_string(), version)].clone())
+    })?;
+    Ok(serde_json::Value::String(rendered))
 }
 
 /// Synchronously resolve secrets using pre-parsed SecretUsage list
@@ -72,15 +89,24 @@ pub fn resolve_secrets_sync(
     let mut result = value.clone();
 
     for usage in usages {
-        let secret_value = secrets.get_sync(&usage.path, &usage.key)?;
-        set_at_path(&mut result, &usage.value_path, secret_value)?;
+        let resolved = match usage {
+            SecretUsage::Value {
+                path, key, version, ..
+            } => secrets.get_sync_at(path, key, *version)?,
+            SecretUsage::Template { parts, .. } => {
+                serde_json::Value::String(render_template(parts, |path, key, version| {
+                    secrets.get_sync_at(path, key, version)
+                })?)
+            }
+        };
+        set_at_path(&mut result, usage.value_path(), resolved)?;
     }
 
     Ok(result)
 }
 
 /// Set value at the given JSON path
-fn set_at_path(
+pub(super) fn set_at_path(
     root: &mut serde_json::Value,
     path: &[JsonPathKey],
     value: serde_json::Value,
@@ -95,12 +121,8 @@ fn set_at_path(
     // Navigate to the parent of the target
     for key in &path[..path.len() - 1] {
         current = match key {
-            JsonPathKey::Field(f) => current
-                .get_mut(f)
-                .ok_or(SettingsError::InvalidSecretPath)?,
-            JsonPathKey::Index(i) => current
-                .get_mut(i)
-                .ok_or(SettingsError::InvalidSecretPath)?,
+            JsonPathKey::Field(f) => current.get_mut(f).ok_or(SettingsError::InvalidSecretPath)?,
+            JsonPathKey::Index(i) => current.get_mut(i).ok_or(SettingsError::InvalidSecretPath)?,
         };
     }
 
@@ -197,7 +219,10 @@ mod tests {
             JsonPathKey::Field("password".to_string()),
         ];
         set_at_path(&mut root, &path, serde_json::json!("secret123")).unwrap();
-        assert_eq!(root["servers"][0]["password"], serde_json::json!("secret123"));
+        assert_eq!(
+            root["servers"][0]["password"],
+            serde_json::json!("secret123")
+        );
     }
 
     #[test]
@@ -233,4 +258,25 @@ mod tests {
         assert_eq!(result, value);
     }
 
+    #[test]
+    fn test_resolve_secrets_sync_template_escapes() {
+        let secrets = SecretsService::new_without_vault();
+        let value = serde_json::json!({"hint": "write $${secret:path:key}"});
+        let usages = crate::secrets::find_secret_usages(&value).unwrap();
+        assert!(usages.is_empty());
+
+        // Escape-only strings are plain values and stay untouched
+        let result = resolve_secrets_sync(&value, &usages, &secrets).unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_template_without_vault() {
+        let secrets = SecretsService::new_without_vault();
+        let value =
+            serde_json::json!({"dsn": "postgres://app:${secret:secret/data/db:password}@db"});
+
+        let result = resolve_secrets(&value, &secrets).await;
+        assert!(matches!(result, Err(SettingsError::SecretWithoutVault)));
+    }
 }
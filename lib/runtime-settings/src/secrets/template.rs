@@ -0,0 +1,234 @@
+// lib/runtime-settings/src/secrets/template.rs
+//! Secret placeholders inside string values.
+//!
+//! `"postgres://app:${secret:secret/data/db:password}@db/app"` is rendered by
+//! replacing each `${secret:path:key}` with the secret value. Strings are
+//! inserted as is, other values as their JSON text. In a template `$${`
+//! renders a literal `${`, so `"${secret:p:k} $${secret:x:y}"` ends in
+//! `"${secret:x:y}"`.
+//!
+//! Placeholders accept the same `path:key@version` references as `$secret`.
+//! Only strings with at least one unescaped `${secret:` are templates; any
+//! other string, including one with only escaped placeholders, is left
+//! untouched.
+
+use super::parse_secret_ref;
+use crate::error::SettingsError;
+
+const PLACEHOLDER: &str = "${secret:";
+
+/// Piece of a parsed template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    Literal(String),
+    Secret {
+        path: String,
+        key: String,
+        version: Option<u64>,
+    },
+}
+
+/// Parse a string value into template parts.
+///
+/// Returns `None` for strings without secret placeholders, including strings
+/// whose only `${secret:` is escaped: those are plain values.
+pub fn parse_template(text: &str) -> Result<Option<Vec<TemplatePart>>, SettingsError> {
+    if !text.contains(PLACEHOLDER) {
+        return Ok(None);
+    }
+
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+
+    while let Some(pos) = rest.find("${") {
+        // `$${` escapes a literal `${`
+        if rest[..pos].ends_with('$') {
+            literal.push_str(&rest[..pos - 1]);
+            literal.push_str("${");
+            rest = &rest[pos + 2..];
+            continue;
+        }
+
+        literal.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        let Some(reference) = rest.strip_prefix(PLACEHOLDER) else {
+            // Not a secret placeholder, keep `${` as text
+            literal.push_str("${");
+            rest = &rest[2..];
+            continue;
+        };
+        let Some(end) = reference.find('}') else {
+            return Err(invalid(rest));
+        };
+        let (path, key, version) = parse_secret_ref(&reference[..end])?;
+
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+        }
+        parts.push(TemplatePart::Secret { path, key, version });
+        rest = &reference[end + 1..];
+    }
+
+    if parts.is_empty() {
+        return Ok(None);
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        parts.push(TemplatePart::Literal(literal));
+    }
+    Ok(Some(parts))
+}
+
+/// Render template parts, looking up each secret with `get(path, key, version)`
+pub fn render_template<F>(parts: &[TemplatePart], mut get: F) -> Result<String, SettingsError>
+where
+    F: FnMut(&str, &str, Option<u64>) -> Result<serde_json::Value, SettingsError>,
+{
+    let mut rendered = String::new();
+    for part in parts {
+        match part {
+            TemplatePart::Literal(text) => rendered.push_str(text),
+            TemplatePart::Secret { path, key, version } => {
+                push_value(&mut rendered, get(path, key, *version)?)
+            }
+        }
+    }
+    Ok(rendered)
+}
+
+/// Append a secret value to a rendered template
+fn push_value(rendered: &mut String, value: serde_json::Value) {
+    match value {
+        serde_json::Value::String(s) => rendered.push_str(&s),
+        other => rendered.push_str(&other.to_string()),
+    }
+}
+
+fn invalid(reference: &str) -> SettingsError {
+    SettingsError::InvalidSecretReference {
+        reference: reference.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(path: &str, key: &str) -> TemplatePart {
+        TemplatePart::Secret {
+            path: path.to_string(),
+            key: key.to_string(),
+            version: None,
+        }
+    }
+
+    fn literal(text: &str) -> TemplatePart {
+        TemplatePart::Literal(text.to_string())
+    }
+
+    #[test]
+    fn test_plain_strings_are_not_templates() {
+        for text in ["", "plain", "cost: $5", "${HOME}/data", "$${secret"] {
+            assert_eq!(parse_template(text).unwrap(), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_parse_dsn() {
+        let parts = parse_template(
+            "postgres://${secret:secret/data/db:user}:${secret:secret/data/db:password}@db/app",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            parts,
+            vec![
+                literal("postgres://"),
+                secret("secret/data/db", "user"),
+                literal(":"),
+                secret("secret/data/db", "password"),
+                literal("@db/app"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whole_string_placeholder() {
+        let parts = parse_template("${secret:database/creds/app:password}")
+            .unwrap()
+            .unwrap();
+        assert_eq!(parts, vec![secret("database/creds/app", "password")]);
+    }
+
+    #[test]
+    fn test_escapes() {
+        let parts = parse_template("$${secret:a:b} ${HOME} ${secret:p:k} $$x")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            parts,
+            vec![
+                literal("${secret:a:b} ${HOME} "),
+                secret("p", "k"),
+                literal(" $$x"),
+            ]
+        );
+
+        // Only escapes: a plain value, not a template without secrets
+        for text in ["$${secret:a:b}", "x $${secret:a:b} $${secret:c:d}"] {
+            assert_eq!(parse_template(text).unwrap(), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_pinned_version() {
+        let parts = parse_template("${secret:secret/data/db:password@5}")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            parts,
+            vec![TemplatePart::Secret {
+                path: "secret/data/db".to_string(),
+                key: "password".to_string(),
+                version: Some(5),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_invalid_placeholders() {
+        for text in ["${secret:path:key", "x ${secret:no-colon} y"] {
+            assert!(
+                matches!(
+                    parse_template(text),
+                    Err(SettingsError::InvalidSecretReference { .. })
+                ),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let parts = parse_template("u=${secret:p:user} port=${secret:p:port}")
+            .unwrap()
+            .unwrap();
+        let rendered = render_template(&parts, |_, key, _| {
+            Ok(match key {
+                "user" => serde_json::json!("app"),
+                _ => serde_json::json!(5432),
+            })
+        })
+        .unwrap();
+        assert_eq!(rendered, "u=app port=5432");
+    }
+
+    #[test]
+    fn test_render_propagates_errors() {
+        let parts = parse_template("${secret:p:k}").unwrap().unwrap();
+        let result = render_template(&parts, |_, _, _| Err(SettingsError::SecretWithoutVault));
+        assert!(matches!(result, Err(SettingsError::SecretWithoutVault)));
+    }
+}
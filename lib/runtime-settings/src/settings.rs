@@ -1,31 +1,48 @@
 // lib/runtime-settings/src/settings.rs
 //! RuntimeSettings - main struct for managing runtime configuration.
 
-use crate::context::{DynamicContext, Request, StaticContext};
+use crate::context::{CustomContext, DynamicContext, Request, StaticContext};
 use crate::entities::Setting;
 use crate::error::SettingsError;
-use crate::filters::check_static_filters;
+use crate::explain::{self, Explanation};
+use crate::filters::{
+    Clock, CompiledDynamicFilter, CompiledStaticFilter, CustomFilter, FilterRegistry, SystemClock,
+    check_static_filters,
+};
 use crate::providers::{
-    EnvProvider, FileProvider, McsProvider, ProviderResponse, SettingsProvider,
+    EnvProvider, FileProvider, McsCache, McsProvider, ProviderResponse, SettingsProvider,
 };
 use crate::scoped::{
-    current_custom, current_request, set_thread_custom, set_thread_request, with_task_custom,
-    with_task_request, CustomContextGuard, RequestGuard,
+    CustomContextGuard, RequestGuard, current_custom, current_request, memoized, set_thread_custom,
+    set_thread_request, with_task_custom, with_task_request,
+};
+use crate::secrets::{SecretsPrefetch, SecretsService, resolve_secrets};
+use crate::validation::{SchemaRegistry, SettingRejected, schema_validator, type_validator};
+use crate::watchers::{
+    DEFAULT_WATCHER_CONCURRENCY, DEFAULT_WATCHER_TIMEOUT, PatternWatcher, Watcher, WatcherId,
+    WatchersService,
 };
-use crate::secrets::SecretsService;
-use crate::watchers::{Watcher, WatcherId, WatchersService};
+use arc_swap::ArcSwap;
 use semver::Version;
-use vault_client::VaultClient;
 use serde::de::DeserializeOwned;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::sync::watch;
+use vault_client::VaultClient;
 
-/// Internal state of RuntimeSettings
+/// Internal state of RuntimeSettings.
+///
+/// Published as an immutable snapshot: writers build a new state off to the side
+/// and swap it in atomically, so readers always observe one consistent version.
+/// Compiled settings are shared between snapshots via `Arc`, which keeps their
+/// per-type value caches alive across merges.
+#[derive(Clone)]
 struct SettingsState {
     version: String,
-    settings: HashMap<String, Vec<Setting>>,
+    settings: HashMap<String, Vec<Arc<Setting>>>,
 }
 
 impl Default for SettingsState {
@@ -37,12 +54,31 @@ impl Default for SettingsState {
     }
 }
 
+/// Source of `RuntimeSettings::instance_id`
+static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Shortest time between push requests that bring no changes, so a provider
+/// answering without holding the request doesn't cause a busy loop
+const MIN_EMPTY_PUSH_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Main runtime settings manager
 pub struct RuntimeSettings {
+    /// Distinguishes instances in the request-scoped memo
+    instance_id: u64,
     providers: Vec<Box<dyn SettingsProvider>>,
-    state: RwLock<SettingsState>,
+    state: ArcSwap<SettingsState>,
+    /// Serializes writers; readers never take it
+    merge_lock: Mutex<()>,
     secrets: SecretsService,
+    secrets_prefetch: SecretsPrefetch,
+    prefetch_concurrency: usize,
     watchers: WatchersService,
+    schemas: SchemaRegistry,
+    /// Custom filters registered on the builder
+    filters: FilterRegistry,
+    mcs_cache: Option<McsCache>,
+    /// Set while pushed updates are flowing; `refresh()` then skips polling MCS
+    push_healthy: AtomicBool,
     pub(crate) static_context: StaticContext,
     pub(crate) refresh_interval: Duration,
 }
@@ -63,7 +99,10 @@ impl RuntimeSettings {
                         settings_count = response.settings.len(),
                         "Loaded settings from provider"
                     );
-                    self.merge_settings(response);
+                    if provider.name() == "mcs" {
+                        self.store_mcs_cache(&response).await;
+                    }
+                    self.merge_settings(provider.name(), response);
                 }
                 Err(e) => {
                     tracing::warn!(
@@ -71,22 +110,56 @@ impl RuntimeSettings {
                         error = %e,
                         "Failed to load settings from provider"
                     );
+                    if provider.name() == "mcs" {
+                        self.load_mcs_cache().await;
+                    }
                     // Continue with other providers
                 }
             }
         }
+
+        if self.secrets_prefetch != SecretsPrefetch::Lazy {
+            self.prefetch_secrets().await?;
+        }
         Ok(())
     }
 
+    /// Fetch every secret referenced by loaded settings into the cache
+    async fn prefetch_secrets(&self) -> Result<(), SettingsError> {
+        let state = self.state.load_full();
+        let usages = state
+            .settings
+            .values()
+            .flatten()
+            .flat_map(|setting| setting.secrets_usages());
+        let references = usages.flat_map(|usage| usage.references());
+
+        let errors = self
+            .secrets
+            .prefetch(references, self.prefetch_concurrency)
+            .await;
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        for error in &errors {
+            tracing::warn!(error = %error, "Failed to prefetch secret");
+        }
+        match self.secrets_prefetch {
+            SecretsPrefetch::FailFast => Err(errors.into_iter().next().unwrap()),
+            _ => Ok(()),
+        }
+    }
+
     /// Refresh settings from MCS and secrets, then check watchers
     pub async fn refresh(&self) -> Result<(), SettingsError> {
-        // Find MCS provider and refresh
+        // Find MCS provider and refresh (unless changes are being pushed)
         for provider in &self.providers {
+            if provider.supports_push() && self.push_healthy.load(Ordering::Acquire) {
+                continue;
+            }
             if provider.name() == "mcs" {
-                let version = {
-                    let state = self.state.read().unwrap();
-                    state.version.clone()
-                };
+                let version = self.state.load().version.clone();
 
                 match provider.load(&version).await {
                     Ok(response) => {
@@ -96,7 +169,7 @@ impl RuntimeSettings {
                             new_version = %response.version,
                             "Refreshed settings from MCS"
                         );
-                        self.merge_settings(response);
+                        self.merge_mcs_delta(provider.name(), response).await;
                     }
                     Err(e) => {
                         tracing::warn!(error = %e, "Failed to refresh settings from MCS");
@@ -109,12 +182,79 @@ impl RuntimeSettings {
         self.secrets.refresh().await?;
 
         // Check watchers
-        let current_values = self.collect_current_values();
-        self.watchers.check(&current_values).await;
+        self.check_watchers().await;
 
         Ok(())
     }
 
+    /// Whether a provider can push changes (see `run_push_updates()`)
+    pub fn push_enabled(&self) -> bool {
+        self.providers.iter().any(|p| p.supports_push())
+    }
+
+    /// Receive pushed settings changes; runs until the future is dropped.
+    ///
+    /// Each delta is merged and watchers are checked as soon as it arrives.
+    /// Deltas not newer than the current settings version are dropped. While
+    /// the push channel is healthy, `refresh()` skips polling MCS. When the
+    /// channel drops, polling takes over and the channel is reconnected after
+    /// `refresh_interval`.
+    pub async fn run_push_updates(&self) {
+        let Some(provider) = self.providers.iter().find(|p| p.supports_push()) else {
+            return;
+        };
+
+        loop {
+            let version = self.state.load().version.clone();
+            let started = tokio::time::Instant::now();
+
+            let changed = match provider.wait_for_changes(&version).await {
+                Ok(Some(response)) => {
+                    self.push_healthy.store(true, Ordering::Release);
+                    tracing::debug!(
+                        provider = provider.name(),
+                        settings_count = response.settings.len(),
+                        deleted_count = response.deleted.len(),
+                        new_version = %response.version,
+                        "Received pushed settings"
+                    );
+                    let changed = !response.settings.is_empty() || !response.deleted.is_empty();
+                    if self.merge_mcs_delta(provider.name(), response).await {
+                        self.check_watchers().await;
+                        changed
+                    } else {
+                        false
+                    }
+                }
+                Ok(None) => {
+                    self.push_healthy.store(true, Ordering::Release);
+                    false
+                }
+                Err(e) => {
+                    if self.push_healthy.swap(false, Ordering::AcqRel) {
+                        tracing::warn!(
+                            provider = provider.name(),
+                            error = %e,
+                            "Settings push channel dropped, falling back to polling"
+                        );
+                    } else {
+                        tracing::debug!(
+                            provider = provider.name(),
+                            error = %e,
+                            "Settings push channel unavailable"
+                        );
+                    }
+                    tokio::time::sleep(self.refresh_interval).await;
+                    continue;
+                }
+            };
+
+            if !changed {
+                tokio::time::sleep_until(started + MIN_EMPTY_PUSH_INTERVAL).await;
+            }
+        }
+    }
+
     /// Refresh settings with a configurable timeout
     pub async fn refresh_with_timeout(&self, timeout: Duration) -> Result<(), SettingsError> {
         tokio::time::timeout(timeout, self.refresh())
@@ -122,13 +262,16 @@ impl RuntimeSettings {
             .map_err(|_| SettingsError::Timeout)?
     }
 
-    /// Get setting value using current scoped context
+    /// Get setting value using current scoped context.
+    ///
+    /// Inside `with_request()` the matching setting is memoized for the rest of
+    /// the request: repeated calls don't re-run filters, `probability` filters
+    /// give the same answer each time, and `explain()` reports the same winner.
     pub fn get<T>(&self, key: &str) -> Option<Arc<T>>
     where
         T: DeserializeOwned + Send + Sync + 'static,
     {
-        let ctx = self.get_dynamic_context();
-        self.get_internal(key, &ctx)
+        self.current_setting(key)?.get_value::<T>(&self.secrets)
     }
 
     /// Get setting value with default
@@ -139,6 +282,35 @@ impl RuntimeSettings {
         self.get(key).unwrap_or_else(|| Arc::new(default))
     }
 
+    /// Get setting value, treating values rejected by `validate` as missing.
+    ///
+    /// The validator runs once per setting version and value type; later calls
+    /// with the same `validator` id reuse its verdict. Give each validation
+    /// function, or closure capturing different state, its own id.
+    pub fn get_validated<T, F, E>(
+        &self,
+        key: &str,
+        validator: &'static str,
+        validate: F,
+    ) -> Option<Arc<T>>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce(&T) -> Result<(), E>,
+        E: std::fmt::Display,
+    {
+        self.current_setting(key)?
+            .get_validated_value(&self.secrets, validator, validate)
+    }
+
+    /// Setting `key` resolves to under the current scoped context, memoized
+    /// for the rest of the request
+    fn current_setting(&self, key: &str) -> Option<Arc<Setting>> {
+        memoized(self.instance_id, key, || {
+            let ctx = self.get_dynamic_context();
+            self.find_setting(key, &ctx)
+        })
+    }
+
     /// Create a getter function for a setting
     pub fn getter<T>(&self, key: &'static str, default: T) -> impl Fn(&RuntimeSettings) -> Arc<T>
     where
@@ -147,16 +319,107 @@ impl RuntimeSettings {
         move |settings| settings.get_or::<T>(key, default.clone())
     }
 
+    /// Explain how `get(key)` resolves under the current scoped context.
+    ///
+    /// Lists every loaded candidate with its priority, provider and per-filter
+    /// results, and which candidate wins. Settings rejected by static filters are
+    /// discarded at load time and do not appear. Inside `with_request()` the
+    /// winner is the setting `get()` returns for the rest of the request.
+    pub fn explain(&self, key: &str) -> Explanation {
+        let ctx = self.get_dynamic_context();
+        let state = self.state.load();
+        let settings = state
+            .settings
+            .get(key)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut explanation = explain::explain(key, settings, &self.static_context, &ctx);
+
+        let winner = memoized(self.instance_id, key, || {
+            explanation.winner.map(|i| Arc::clone(&settings[i]))
+        });
+        explanation.winner =
+            winner.and_then(|winner| settings.iter().position(|s| Arc::ptr_eq(s, &winner)));
+        explanation
+    }
+
+    /// Custom filters available to this instance
+    pub fn filters(&self) -> &FilterRegistry {
+        &self.filters
+    }
+
     /// Add a watcher for a setting
     pub fn add_watcher(&self, key: &str, watcher: Watcher) -> WatcherId {
         self.watchers.add(key, watcher)
     }
 
+    /// Add a watcher for a setting as seen under `ctx`.
+    ///
+    /// Watchers added with `add_watcher` see the value under the context of the
+    /// refresh task, which usually has no request or custom values. This one
+    /// evaluates dynamic filters against `ctx`, e.g. to follow the value a
+    /// specific tenant gets.
+    pub fn add_watcher_with_context(
+        &self,
+        key: &str,
+        ctx: DynamicContext,
+        watcher: Watcher,
+    ) -> WatcherId {
+        self.watchers.add_with_context(key, ctx, watcher)
+    }
+
+    /// Add a watcher for a setting as seen with custom context `values`
+    pub fn add_watcher_with_custom(
+        &self,
+        key: &str,
+        values: HashMap<String, String>,
+        watcher: Watcher,
+    ) -> WatcherId {
+        let mut custom = CustomContext::new();
+        custom.push_layer(values);
+        let ctx = DynamicContext {
+            request: None,
+            custom,
+        };
+        self.watchers.add_with_context(key, ctx, watcher)
+    }
+
+    /// Add a watcher for every setting whose key matches `pattern`, e.g. `FEATURE_*`.
+    ///
+    /// The callback gets the keys whose values changed, including keys that
+    /// appeared or were deleted since the last check.
+    pub fn add_pattern_watcher(&self, pattern: &str, watcher: PatternWatcher) -> WatcherId {
+        self.watchers.add_pattern(pattern, watcher)
+    }
+
     /// Remove a watcher by ID
     pub fn remove_watcher(&self, id: WatcherId) {
         self.watchers.remove(id)
     }
 
+    /// Watch a setting as typed values.
+    ///
+    /// The receiver starts with the current value and is updated by the same
+    /// change detection as watchers, in `refresh()` and `run_push_updates()`.
+    /// Like watchers, it sees the value without request or custom context,
+    /// wherever it is called from.
+    /// Missing values and values that don't deserialize into `T` are `None`.
+    /// Dropping the last receiver unsubscribes.
+    pub async fn watch<T>(&self, key: &str) -> watch::Receiver<Option<Arc<T>>>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let state = self.state.load_full();
+        let current = match state.settings.get(key) {
+            Some(settings) => self
+                .current_value(key, settings, &Self::watcher_context())
+                .await
+                .unwrap_or_default(),
+            None => None,
+        };
+        self.watchers.subscribe(key, current)
+    }
+
     /// Set thread-local request
     pub fn set_request(&self, req: Request) -> RequestGuard {
         set_thread_request(req)
@@ -191,12 +454,9 @@ impl RuntimeSettings {
         }
     }
 
-    /// Internal get with explicit context
-    fn get_internal<T>(&self, key: &str, ctx: &DynamicContext) -> Option<Arc<T>>
-    where
-        T: DeserializeOwned + Send + Sync + 'static,
-    {
-        let state = self.state.read().unwrap();
+    /// First setting for `key` matching `ctx`, with a fresh value cache
+    fn find_setting(&self, key: &str, ctx: &DynamicContext) -> Option<Arc<Setting>> {
+        let state = self.state.load();
 
         let settings = state.settings.get(key)?;
 
@@ -209,54 +469,158 @@ impl RuntimeSettings {
                     setting.invalidate_if_stale(self.secrets.version());
                 }
 
-                return setting.get_value::<T>(&self.secrets);
+                return Some(Arc::clone(setting));
             }
         }
 
         None
     }
 
-    /// Merge provider response into state
-    fn merge_settings(&self, response: ProviderResponse) {
-        let mut state = self.state.write().unwrap();
+    /// Fall back to the last-known-good MCS settings from the on-disk cache
+    async fn load_mcs_cache(&self) {
+        let Some(cache) = &self.mcs_cache else {
+            return;
+        };
+
+        match cache.load().await {
+            Ok(response) => {
+                tracing::warn!(
+                    path = %cache.path().display(),
+                    settings_count = response.settings.len(),
+                    version = %response.version,
+                    "Loaded MCS settings from last-known-good cache"
+                );
+                self.merge_settings("mcs", response);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    path = %cache.path().display(),
+                    error = %e,
+                    "Failed to load MCS settings cache"
+                );
+            }
+        }
+    }
+
+    /// Persist a successful MCS response to the on-disk cache
+    async fn store_mcs_cache(&self, response: &ProviderResponse) {
+        let Some(cache) = &self.mcs_cache else {
+            return;
+        };
+
+        if let Err(e) = cache.store(response).await {
+            tracing::warn!(
+                path = %cache.path().display(),
+                error = %e,
+                "Failed to write MCS settings cache"
+            );
+        }
+    }
+
+    /// Position of a provider in the provider list (`None` for unknown providers)
+    fn provider_rank(&self, provider: &str) -> Option<usize> {
+        self.providers.iter().position(|p| p.name() == provider)
+    }
+
+    /// Merge an MCS delta from `refresh()` or a push and cache it on disk.
+    ///
+    /// Polling and push run concurrently, so a delta whose version is not newer
+    /// than the current settings is dropped. Returns whether it was merged.
+    async fn merge_mcs_delta(&self, provider: &str, response: ProviderResponse) -> bool {
+        let cached = response.clone();
+        let merged = {
+            let _merge_guard = self.merge_lock.lock().unwrap();
+            let current = self.state.load().version.clone();
+            let newer = is_newer_version(&response.version, &current);
+            if newer {
+                self.merge_locked(provider, response);
+            } else if !response.settings.is_empty() || !response.deleted.is_empty() {
+                tracing::debug!(
+                    provider,
+                    version = %response.version,
+                    current_version = %current,
+                    "Dropping settings delta that is not newer than the current version"
+                );
+            }
+            newer
+        };
+        if merged {
+            self.store_mcs_cache(&cached).await;
+        }
+        merged
+    }
+
+    /// Merge provider response into a new state snapshot and publish it atomically
+    fn merge_settings(&self, provider: &str, response: ProviderResponse) {
+        let _merge_guard = self.merge_lock.lock().unwrap();
+        self.merge_locked(provider, response);
+    }
 
-        // Process deleted settings first
+    /// `merge_settings` with `merge_lock` already held
+    fn merge_locked(&self, provider: &str, response: ProviderResponse) {
+        let mut state = SettingsState::clone(&self.state.load());
+
+        // Process deleted settings first. Merges are scoped to the provider,
+        // so a delete never removes entries loaded from another provider.
         for deleted in &response.deleted {
             if let Some(settings) = state.settings.get_mut(&deleted.key) {
-                settings.retain(|s| s.priority != deleted.priority);
+                settings.retain(|s| !(s.provider == provider && s.priority == deleted.priority));
             }
         }
 
         // Process new/updated settings
         for raw_setting in response.settings {
             // Check static filters before compiling
-            if !check_static_filters(&raw_setting.filter, &self.static_context) {
+            if !check_static_filters(&raw_setting.filter, &self.static_context, &self.filters) {
                 // Setting doesn't match static filters, remove if exists
                 if let Some(settings) = state.settings.get_mut(&raw_setting.key) {
-                    settings.retain(|s| s.priority != raw_setting.priority);
+                    settings.retain(|s| {
+                        !(s.provider == provider && s.priority == raw_setting.priority)
+                    });
                 }
                 continue;
             }
 
             // Compile the raw setting
-            let setting = match Setting::compile(raw_setting) {
-                Ok(s) => s,
+            let setting = match Setting::compile(raw_setting, &self.filters) {
+                Ok(mut s) => {
+                    s.provider = provider.to_string();
+                    s.provider_version = response.version.clone();
+                    Arc::new(s)
+                }
                 Err(e) => {
                     tracing::warn!(error = %e, "Failed to compile setting filters, skipping");
                     continue;
                 }
             };
 
+            // Reject values that fail the key's validator, keeping the previous
+            // value. Values with secret references are only known after
+            // resolution and are not validated here.
+            if !setting.has_secrets()
+                && !self
+                    .schemas
+                    .check(provider, &setting.key, setting.priority, &setting.value)
+            {
+                continue;
+            }
+
             // Add or update setting
             let settings = state.settings.entry(setting.key.clone()).or_default();
 
-            // Remove existing setting with same priority
-            settings.retain(|s| s.priority != setting.priority);
+            // Replace this provider's setting with the same priority
+            settings.retain(|s| !(s.provider == provider && s.priority == setting.priority));
 
-            // Insert in priority order (highest first)
+            // Insert in priority order (highest first). On equal priority the
+            // provider registered later wins, as it did before merges were scoped.
+            let rank = self.provider_rank(provider);
             let pos = settings
                 .iter()
-                .position(|s| s.priority < setting.priority)
+                .position(|s| {
+                    s.priority < setting.priority
+                        || (s.priority == setting.priority
+                            && self.provider_rank(&s.provider) < rank)
+                })
                 .unwrap_or(settings.len());
             settings.insert(pos, setting);
         }
@@ -265,24 +629,97 @@ impl RuntimeSettings {
         if !response.version.is_empty() {
             state.version = response.version;
         }
+
+        self.state.store(Arc::new(state));
     }
 
-    /// Collect current values for watched settings
-    fn collect_current_values(&self) -> HashMap<String, serde_json::Value> {
-        let state = self.state.read().unwrap();
-        let mut values = HashMap::new();
+    /// Notify watchers and subscribers of changed values.
+    ///
+    /// Only watched keys are resolved. Keys whose secrets fail to resolve keep
+    /// their last value until a later refresh succeeds.
+    async fn check_watchers(&self) {
+        let state = self.state.load_full();
+        let mut current_values = HashMap::new();
+        let mut unresolved = HashSet::new();
 
-        let ctx = self.get_dynamic_context();
+        let ctx = Self::watcher_context();
         for (key, settings) in &state.settings {
-            for setting in settings {
-                if setting.check_dynamic_filters(&ctx) {
-                    values.insert(key.clone(), setting.value.clone());
-                    break;
+            if !self.watchers.is_watched(key) {
+                continue;
+            }
+            match self.current_value(key, settings, &ctx).await {
+                Ok(Some(value)) => {
+                    current_values.insert(key.clone(), value);
+                }
+                Ok(None) => {}
+                Err(_) => {
+                    unresolved.insert(key.clone());
+                }
+            }
+        }
+
+        let mut context_values = HashMap::new();
+        for (id, key, ctx) in self.watchers.context_targets() {
+            let Some(settings) = state.settings.get(&key) else {
+                continue;
+            };
+            match self.current_value(&key, settings, &ctx).await {
+                Ok(Some(value)) => {
+                    context_values.insert(id, value);
+                }
+                Ok(None) => {}
+                Err(_) => {
+                    unresolved.insert(key);
                 }
             }
         }
 
-        values
+        let errors = self
+            .watchers
+            .check(&current_values, &context_values, &unresolved)
+            .await;
+        for error in &errors {
+            tracing::warn!(error = %error, "Watcher callback failed");
+        }
+    }
+
+    /// Context for watchers, pattern watchers and subscribers: no request and
+    /// no custom values, whether `refresh()` runs in the background task or is
+    /// called inside a request scope
+    fn watcher_context() -> DynamicContext {
+        DynamicContext::default()
+    }
+
+    /// Resolved value of the first candidate matching `ctx`.
+    ///
+    /// Secret references are resolved, so watchers also see rotated secrets.
+    async fn current_value(
+        &self,
+        key: &str,
+        settings: &[Arc<Setting>],
+        ctx: &DynamicContext,
+    ) -> Result<Option<serde_json::Value>, SettingsError> {
+        let Some(setting) = settings.iter().find(|s| s.check_dynamic_filters(ctx)) else {
+            return Ok(None);
+        };
+        if !setting.has_secrets() {
+            return Ok(Some(setting.value.clone()));
+        }
+        resolve_secrets(&setting.value, &self.secrets)
+            .await
+            .map(Some)
+            .inspect_err(|e| {
+                tracing::warn!(key = %key, error = %e, "Failed to resolve secrets for watchers");
+            })
+    }
+}
+
+/// Whether MCS `version` is newer than `current`. Numeric versions are compared
+/// as numbers; any other non-empty version counts as newer when it differs.
+fn is_newer_version(version: &str, current: &str) -> bool {
+    match (version.parse::<u64>(), current.parse::<u64>()) {
+        (Ok(version), Ok(current)) => version > current,
+        _ => !version.is_empty() && version != current,
     }
 }
 
@@ -295,10 +732,20 @@ pub struct RuntimeSettingsBuilder {
     mcs_run_env: Option<String>,
     mcs_enabled: bool,
     mcs_base_url: Option<String>,
+    mcs_cache_path: Option<String>,
+    mcs_long_poll: Option<Duration>,
     file_path: Option<String>,
     env_enabled: bool,
     refresh_interval: Duration,
     vault_client: Option<VaultClient>,
+    secrets_prefetch: SecretsPrefetch,
+    prefetch_concurrency: usize,
+    watcher_concurrency: usize,
+    watcher_timeout: Duration,
+    schemas: SchemaRegistry,
+    json_schemas: Vec<(String, serde_json::Value)>,
+    custom_filters: Vec<(String, CustomFilter)>,
+    clock: Arc<dyn Clock>,
 }
 
 impl RuntimeSettingsBuilder {
@@ -316,10 +763,20 @@ impl RuntimeSettingsBuilder {
             mcs_run_env,
             mcs_enabled: true,
             mcs_base_url: None,
+            mcs_cache_path: None,
+            mcs_long_poll: None,
             file_path: None,
             env_enabled: true,
             refresh_interval: Duration::from_secs(30),
             vault_client: None,
+            secrets_prefetch: SecretsPrefetch::Lazy,
+            prefetch_concurrency: 8,
+            watcher_concurrency: DEFAULT_WATCHER_CONCURRENCY,
+            watcher_timeout: DEFAULT_WATCHER_TIMEOUT,
+            schemas: SchemaRegistry::new(),
+            json_schemas: Vec::new(),
+            custom_filters: Vec::new(),
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -353,6 +810,24 @@ impl RuntimeSettingsBuilder {
         self
     }
 
+    /// Set path of the on-disk last-known-good cache for MCS settings.
+    ///
+    /// The cache is rewritten after every successful MCS load and used at
+    /// `init()` when MCS is unreachable.
+    pub fn mcs_cache_path(mut self, path: impl Into<String>) -> Self {
+        self.mcs_cache_path = Some(path.into());
+        self
+    }
+
+    /// Enable push mode for MCS: changes are long-polled and applied as soon as
+    /// the version changes. MCS may hold each request for up to `timeout`.
+    ///
+    /// Pushed updates are received by `run_push_updates()`, which `setup()` spawns.
+    pub fn mcs_long_poll(mut self, timeout: Duration) -> Self {
+        self.mcs_long_poll = Some(timeout);
+        self
+    }
+
     /// Set file path for file provider
     pub fn file_path(mut self, path: impl Into<String>) -> Self {
         self.file_path = Some(path.into());
@@ -377,8 +852,105 @@ impl RuntimeSettingsBuilder {
         self
     }
 
+    /// Fetch secrets referenced by settings at `init()` instead of on first use
+    pub fn prefetch_secrets(mut self, mode: SecretsPrefetch) -> Self {
+        self.secrets_prefetch = mode;
+        self
+    }
+
+    /// Set how many Vault paths are fetched at once during prefetch
+    pub fn prefetch_concurrency(mut self, limit: usize) -> Self {
+        self.prefetch_concurrency = limit;
+        self
+    }
+
+    /// Set how many watcher callbacks run at once
+    pub fn watcher_concurrency(mut self, limit: usize) -> Self {
+        self.watcher_concurrency = limit;
+        self
+    }
+
+    /// Set how long a watcher callback may run before it is cancelled
+    pub fn watcher_timeout(mut self, timeout: Duration) -> Self {
+        self.watcher_timeout = timeout;
+        self
+    }
+
+    /// Require values of `key` to deserialize into `T`.
+    ///
+    /// Invalid values are rejected when merged and the previous value is kept.
+    pub fn validate_type<T>(mut self, key: impl Into<String>) -> Self
+    where
+        T: DeserializeOwned + 'static,
+    {
+        self.schemas.register(key, type_validator::<T>());
+        self
+    }
+
+    /// Require values of `key` to match a JSON Schema.
+    ///
+    /// Invalid values are rejected when merged and the previous value is kept.
+    /// An invalid schema fails `build()`.
+    pub fn validate_schema(mut self, key: impl Into<String>, schema: serde_json::Value) -> Self {
+        self.json_schemas.push((key.into(), schema));
+        self
+    }
+
+    /// Set a callback invoked for every setting rejected by validation
+    pub fn on_reject<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SettingRejected) + Send + Sync + 'static,
+    {
+        self.schemas.set_on_reject(Box::new(hook));
+        self
+    }
+
+    /// Register a custom static filter, checked once when settings are loaded.
+    ///
+    /// `compile` is called with the filter pattern. Filters only apply to the
+    /// instance this builder builds; a built-in filter name fails `build()`.
+    pub fn register_static_filter<F>(mut self, name: impl Into<String>, compile: F) -> Self
+    where
+        F: Fn(&str) -> Result<Box<dyn CompiledStaticFilter>, SettingsError> + Send + Sync + 'static,
+    {
+        self.custom_filters
+            .push((name.into(), CustomFilter::Static(Arc::new(compile))));
+        self
+    }
+
+    /// Register a custom dynamic filter, checked on every `get()`.
+    ///
+    /// `compile` is called with the filter pattern. Filters only apply to the
+    /// instance this builder builds; a built-in filter name fails `build()`.
+    pub fn register_dynamic_filter<F>(mut self, name: impl Into<String>, compile: F) -> Self
+    where
+        F: Fn(&str) -> Result<Box<dyn CompiledDynamicFilter>, SettingsError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.custom_filters
+            .push((name.into(), CustomFilter::Dynamic(Arc::new(compile))));
+        self
+    }
+
+    /// Set the clock used by `active-from`, `active-until` and `schedule`
+    /// filters and by `explain()`. Defaults to the system time.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Build the RuntimeSettings instance
-    pub fn build(self) -> Result<RuntimeSettings, SettingsError> {
+    pub fn build(mut self) -> Result<RuntimeSettings, SettingsError> {
+        let mut filters = FilterRegistry::new().with_clock(self.clock);
+        for (name, filter) in self.custom_filters.drain(..) {
+            filters.register(name, filter)?;
+        }
+        for (key, schema) in &self.json_schemas {
+            self.schemas.register(key, schema_validator(key, schema)?);
+        }
+
         let mut providers: Vec<Box<dyn SettingsProvider>> = Vec::new();
 
         // Add env provider first (lowest priority)
@@ -393,19 +965,27 @@ impl RuntimeSettingsBuilder {
 
         // Add MCS provider last (to get the latest settings)
         if self.mcs_enabled {
-            let base_url = self.mcs_base_url.or_else(|| {
-                self.environment.get("RUNTIME_SETTINGS_BASE_URL").cloned()
-            });
+            let base_url = self
+                .mcs_base_url
+                .or_else(|| self.environment.get("RUNTIME_SETTINGS_BASE_URL").cloned());
             let base_url = base_url.ok_or_else(|| {
                 SettingsError::MissingConfig("RUNTIME_SETTINGS_BASE_URL".to_string())
             })?;
-            providers.push(Box::new(McsProvider::new(
-                base_url,
-                self.application.clone(),
-                self.mcs_run_env.clone(),
-            )));
+            let mut provider =
+                McsProvider::new(base_url, self.application.clone(), self.mcs_run_env.clone());
+            if let Some(timeout) = self.mcs_long_poll {
+                provider = provider.with_long_poll(timeout);
+            }
+            providers.push(Box::new(provider));
         }
 
+        let mcs_cache = if self.mcs_enabled {
+            self.mcs_cache_path
+                .map(|path| McsCache::new(PathBuf::from(path)))
+        } else {
+            None
+        };
+
         let static_context = StaticContext {
             application: self.application,
             server: self.server,
@@ -418,13 +998,22 @@ impl RuntimeSettingsBuilder {
         let secrets = match self.vault_client {
             Some(client) => SecretsService::new(client),
             None => SecretsService::new_without_vault(),
-        };
+        }
+        .with_refresh_interval(self.refresh_interval);
 
         Ok(RuntimeSettings {
+            instance_id: NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed),
             providers,
-            state: RwLock::new(SettingsState::default()),
+            state: ArcSwap::from_pointee(SettingsState::default()),
+            merge_lock: Mutex::new(()),
             secrets,
-            watchers: WatchersService::new(),
+            secrets_prefetch: self.secrets_prefetch,
+            prefetch_concurrency: self.prefetch_concurrency,
+            watchers: WatchersService::with_limits(self.watcher_concurrency, self.watcher_timeout),
+            schemas: self.schemas,
+            filters,
+            mcs_cache,
+            push_healthy: AtomicBool::new(false),
             static_context,
             refresh_interval: self.refresh_interval,
         })
@@ -440,8 +1029,9 @@ impl Default for RuntimeSettingsBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::entities::RawSetting;
+    use crate::entities::{RawSetting, SettingKey};
     use std::sync::Arc;
+    use std::sync::atomic::AtomicU32;
 
     #[test]
     fn test_builder_basic() {
@@ -501,6 +1091,7 @@ mod tests {
             method: "GET".to_string(),
             path: "/api".to_string(),
             headers: HashMap::new(),
+            ..Default::default()
         };
 
         let _guard = settings.set_request(req);
@@ -530,9 +1121,9 @@ mod tests {
             version: "1".to_string(),
         };
 
-        settings.merge_settings(response);
+        settings.merge_settings("test", response);
 
-        let state = settings.state.read().unwrap();
+        let state = settings.state.load();
         assert_eq!(state.version, "1");
         assert!(state.settings.contains_key("MY_KEY"));
         assert_eq!(state.settings["MY_KEY"].len(), 1);
@@ -562,7 +1153,7 @@ mod tests {
             deleted: vec![],
             version: "1".to_string(),
         };
-        settings.merge_settings(response1);
+        settings.merge_settings("test", response1);
 
         // Add high priority setting
         let response2 = ProviderResponse {
@@ -575,9 +1166,9 @@ mod tests {
             deleted: vec![],
             version: "2".to_string(),
         };
-        settings.merge_settings(response2);
+        settings.merge_settings("test", response2);
 
-        let state = settings.state.read().unwrap();
+        let state = settings.state.load();
         assert_eq!(state.settings["MY_KEY"].len(), 2);
         // Highest priority should be first
         assert_eq!(state.settings["MY_KEY"][0].priority, 100);
@@ -585,7 +1176,322 @@ mod tests {
     }
 
     #[test]
-    fn test_get_internal_returns_highest_priority() {
+    fn test_merge_settings_publishes_new_snapshot() {
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .build()
+            .unwrap();
+
+        settings.merge_settings(
+            "test",
+            ProviderResponse {
+                settings: vec![RawSetting {
+                    key: "MY_KEY".to_string(),
+                    priority: 10,
+                    filter: HashMap::new(),
+                    value: serde_json::json!("old_value"),
+                }],
+                deleted: vec![],
+                version: "1".to_string(),
+            },
+        );
+
+        // Reader holds on to the current snapshot
+        let before = settings.state.load_full();
+
+        settings.merge_settings(
+            "test",
+            ProviderResponse {
+                settings: vec![
+                    RawSetting {
+                        key: "MY_KEY".to_string(),
+                        priority: 10,
+                        filter: HashMap::new(),
+                        value: serde_json::json!("new_value"),
+                    },
+                    RawSetting {
+                        key: "OTHER_KEY".to_string(),
+                        priority: 10,
+                        filter: HashMap::new(),
+                        value: serde_json::json!(1),
+                    },
+                ],
+                deleted: vec![],
+                version: "2".to_string(),
+            },
+        );
+
+        // Old snapshot is untouched
+        assert_eq!(before.version, "1");
+        assert_eq!(
+            before.settings["MY_KEY"][0].value,
+            serde_json::json!("old_value")
+        );
+        assert!(!before.settings.contains_key("OTHER_KEY"));
+
+        // New snapshot has the whole update
+        let after = settings.state.load();
+        assert_eq!(after.version, "2");
+        assert_eq!(
+            after.settings["MY_KEY"][0].value,
+            serde_json::json!("new_value")
+        );
+        assert!(after.settings.contains_key("OTHER_KEY"));
+    }
+
+    #[test]
+    fn test_merge_settings_keeps_unchanged_settings_shared() {
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .build()
+            .unwrap();
+
+        settings.merge_settings(
+            "test",
+            ProviderResponse {
+                settings: vec![RawSetting {
+                    key: "STABLE_KEY".to_string(),
+                    priority: 10,
+                    filter: HashMap::new(),
+                    value: serde_json::json!("value"),
+                }],
+                deleted: vec![],
+                version: "1".to_string(),
+            },
+        );
+        let before = Arc::clone(&settings.state.load().settings["STABLE_KEY"][0]);
+
+        settings.merge_settings(
+            "test",
+            ProviderResponse {
+                settings: vec![RawSetting {
+                    key: "OTHER_KEY".to_string(),
+                    priority: 10,
+                    filter: HashMap::new(),
+                    value: serde_json::json!("value"),
+                }],
+                deleted: vec![],
+                version: "2".to_string(),
+            },
+        );
+
+        // Compiled setting (and its value cache) is carried over, not recompiled
+        let after = &settings.state.load().settings["STABLE_KEY"][0];
+        assert!(Arc::ptr_eq(&before, after));
+    }
+
+    fn raw(key: &str, priority: i64, value: serde_json::Value) -> RawSetting {
+        RawSetting {
+            key: key.to_string(),
+            priority,
+            filter: HashMap::new(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_merge_settings_records_provider_origin() {
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .build()
+            .unwrap();
+
+        settings.merge_settings(
+            "mcs",
+            ProviderResponse {
+                settings: vec![raw("MY_KEY", 10, serde_json::json!(1))],
+                deleted: vec![],
+                version: "42".to_string(),
+            },
+        );
+
+        let state = settings.state.load();
+        let setting = &state.settings["MY_KEY"][0];
+        assert_eq!(setting.provider, "mcs");
+        assert_eq!(setting.provider_version, "42");
+    }
+
+    #[test]
+    fn test_merge_settings_delete_is_scoped_to_provider() {
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .build()
+            .unwrap();
+
+        settings.merge_settings(
+            "file",
+            ProviderResponse {
+                settings: vec![raw("MY_KEY", 100, serde_json::json!("file"))],
+                deleted: vec![],
+                version: String::new(),
+            },
+        );
+        settings.merge_settings(
+            "mcs",
+            ProviderResponse {
+                settings: vec![raw("MY_KEY", 100, serde_json::json!("mcs"))],
+                deleted: vec![],
+                version: "1".to_string(),
+            },
+        );
+        assert_eq!(settings.state.load().settings["MY_KEY"].len(), 2);
+
+        settings.merge_settings(
+            "mcs",
+            ProviderResponse {
+                settings: vec![],
+                deleted: vec![SettingKey {
+                    key: "MY_KEY".to_string(),
+                    priority: 100,
+                }],
+                version: "2".to_string(),
+            },
+        );
+
+        let state = settings.state.load();
+        let remaining = &state.settings["MY_KEY"];
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].provider, "file");
+        assert_eq!(remaining[0].value, serde_json::json!("file"));
+    }
+
+    #[test]
+    fn test_merge_settings_equal_priority_later_provider_wins() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, "[]").unwrap();
+
+        // Providers are registered as env, then file
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(true)
+            .file_path(path.to_str().unwrap())
+            .build()
+            .unwrap();
+
+        settings.merge_settings(
+            "file",
+            ProviderResponse {
+                settings: vec![raw("MY_KEY", 100, serde_json::json!("file"))],
+                deleted: vec![],
+                version: String::new(),
+            },
+        );
+        // Merge order does not matter, only provider order
+        settings.merge_settings(
+            "env",
+            ProviderResponse {
+                settings: vec![raw("MY_KEY", 100, serde_json::json!("env"))],
+                deleted: vec![],
+                version: String::new(),
+            },
+        );
+
+        let value: Option<Arc<String>> = settings.get("MY_KEY");
+        assert_eq!(value.as_deref().map(String::as_str), Some("file"));
+        assert_eq!(settings.state.load().settings["MY_KEY"].len(), 2);
+    }
+
+    #[test]
+    fn test_merge_settings_rejects_invalid_values() {
+        let rejected = Arc::new(Mutex::new(Vec::new()));
+        let rejected_clone = rejected.clone();
+
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .validate_type::<u32>("POOL_SIZE")
+            .validate_schema("MODE", serde_json::json!({"enum": ["fast", "safe"]}))
+            .on_reject(move |r| rejected_clone.lock().unwrap().push(r.key.clone()))
+            .build()
+            .unwrap();
+
+        settings.merge_settings(
+            "mcs",
+            ProviderResponse {
+                settings: vec![
+                    raw("POOL_SIZE", 10, serde_json::json!(8)),
+                    raw("MODE", 10, serde_json::json!("fast")),
+                ],
+                deleted: vec![],
+                version: "1".to_string(),
+            },
+        );
+        settings.merge_settings(
+            "mcs",
+            ProviderResponse {
+                settings: vec![
+                    raw("POOL_SIZE", 10, serde_json::json!("eight")),
+                    raw("MODE", 10, serde_json::json!("reckless")),
+                    raw("UNVALIDATED", 10, serde_json::json!("anything")),
+                ],
+                deleted: vec![],
+                version: "2".to_string(),
+            },
+        );
+
+        // Previous values are kept
+        let pool: Option<Arc<u32>> = settings.get("POOL_SIZE");
+        assert_eq!(pool.as_deref(), Some(&8));
+        let mode: Option<Arc<String>> = settings.get("MODE");
+        assert_eq!(mode.as_deref().map(String::as_str), Some("fast"));
+        assert!(settings.get::<String>("UNVALIDATED").is_some());
+
+        let mut rejected = rejected.lock().unwrap().clone();
+        rejected.sort();
+        assert_eq!(rejected, vec!["MODE", "POOL_SIZE"]);
+    }
+
+    #[test]
+    fn test_merge_settings_skips_validation_of_secret_values() {
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .validate_type::<String>("DB_PASSWORD")
+            .build()
+            .unwrap();
+
+        settings.merge_settings(
+            "mcs",
+            ProviderResponse {
+                settings: vec![raw(
+                    "DB_PASSWORD",
+                    10,
+                    serde_json::json!({"$secret": "secret/data/db:password"}),
+                )],
+                deleted: vec![],
+                version: "1".to_string(),
+            },
+        );
+
+        assert!(settings.state.load().settings.contains_key("DB_PASSWORD"));
+    }
+
+    #[test]
+    fn test_build_fails_on_invalid_schema() {
+        let result = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .validate_schema("KEY", serde_json::json!({"type": 42}))
+            .build();
+
+        assert!(matches!(result, Err(SettingsError::InvalidSchema { .. })));
+    }
+
+    #[test]
+    fn test_find_setting_returns_highest_priority() {
         let settings = RuntimeSettings::builder()
             .application("test-app")
             .mcs_enabled(false)
@@ -612,14 +1518,74 @@ mod tests {
             deleted: vec![],
             version: "1".to_string(),
         };
-        settings.merge_settings(response);
+        settings.merge_settings("test", response);
 
         let ctx = DynamicContext::default();
 
-        let result: Option<Arc<String>> = settings.get_internal("MY_KEY", &ctx);
+        let result: Option<Arc<String>> = settings
+            .find_setting("MY_KEY", &ctx)
+            .and_then(|s| s.get_value(&settings.secrets));
         assert_eq!(result.as_deref(), Some(&"high_priority".to_string()));
     }
 
+    #[test]
+    fn test_explain_reports_provider_and_winner() {
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .build()
+            .unwrap();
+
+        settings.merge_settings(
+            "file",
+            ProviderResponse {
+                settings: vec![RawSetting {
+                    key: "MY_KEY".to_string(),
+                    priority: 100,
+                    filter: [("url-path".to_string(), "/admin/.*".to_string())].into(),
+                    value: serde_json::json!("admin"),
+                }],
+                deleted: vec![],
+                version: String::new(),
+            },
+        );
+        settings.merge_settings(
+            "env",
+            ProviderResponse {
+                settings: vec![RawSetting {
+                    key: "MY_KEY".to_string(),
+                    priority: 10,
+                    filter: HashMap::new(),
+                    value: serde_json::json!("default"),
+                }],
+                deleted: vec![],
+                version: String::new(),
+            },
+        );
+
+        let req = Request {
+            method: "GET".to_string(),
+            path: "/api".to_string(),
+            headers: HashMap::new(),
+            ..Default::default()
+        };
+        let _guard = settings.set_request(req);
+
+        let explanation = settings.explain("MY_KEY");
+        assert_eq!(explanation.candidates.len(), 2);
+        assert_eq!(explanation.candidates[0].provider, "file");
+        assert!(!explanation.candidates[0].matched);
+        assert_eq!(explanation.candidates[1].provider, "env");
+        assert_eq!(explanation.winner, Some(1));
+
+        // Consistent with get()
+        let value: Option<Arc<String>> = settings.get("MY_KEY");
+        assert_eq!(value.as_deref().map(String::as_str), Some("default"));
+
+        assert!(settings.explain("MISSING").candidates.is_empty());
+    }
+
     #[tokio::test]
     async fn test_with_request_async() {
         let settings = Arc::new(
@@ -642,12 +1608,13 @@ mod tests {
             deleted: vec![],
             version: "1".to_string(),
         };
-        settings.merge_settings(response);
+        settings.merge_settings("test", response);
 
         let req = Request {
             method: "GET".to_string(),
             path: "/api".to_string(),
             headers: HashMap::new(),
+            ..Default::default()
         };
 
         let result = settings
@@ -682,7 +1649,7 @@ mod tests {
             deleted: vec![],
             version: "1".to_string(),
         };
-        settings.merge_settings(response);
+        settings.merge_settings("test", response);
 
         let custom: HashMap<String, String> = [("key".to_string(), "value".to_string())].into();
 
@@ -696,12 +1663,414 @@ mod tests {
         assert_eq!(result.as_deref(), Some(&"async_value".to_string()));
     }
 
+    fn test_settings() -> RuntimeSettings {
+        RuntimeSettings::builder()
+            .application("test-app")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .build()
+            .unwrap()
+    }
+
+    fn set_value(settings: &RuntimeSettings, setting: RawSetting) {
+        settings.merge_settings(
+            "test",
+            ProviderResponse {
+                settings: vec![setting],
+                deleted: vec![],
+                version: "1".to_string(),
+            },
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_memoized_within_request() {
+        let settings = test_settings();
+        set_value(&settings, raw("KEY", 0, serde_json::json!("old")));
+
+        let (first, second) = settings
+            .with_request(Request::default(), async {
+                let first: Option<Arc<String>> = settings.get("KEY");
+                set_value(&settings, raw("KEY", 0, serde_json::json!("new")));
+                let second: Option<Arc<String>> = settings.get("KEY");
+                (first, second)
+            })
+            .await;
+
+        // The request keeps the value it saw first, the same Arc
+        assert!(Arc::ptr_eq(
+            first.as_ref().unwrap(),
+            second.as_ref().unwrap()
+        ));
+        assert_eq!(second.as_deref().map(String::as_str), Some("old"));
+
+        // Outside the request the new value is visible
+        let value: Option<Arc<String>> = settings.get("KEY");
+        assert_eq!(value.as_deref().map(String::as_str), Some("new"));
+    }
+
+    #[tokio::test]
+    async fn test_get_memoized_probability_is_stable() {
+        let settings = test_settings();
+        set_value(
+            &settings,
+            RawSetting {
+                filter: [("probability".to_string(), "50".to_string())].into(),
+                ..raw("FLAG", 10, serde_json::json!(true))
+            },
+        );
+
+        for _ in 0..20 {
+            settings
+                .with_request(Request::default(), async {
+                    let first = settings.get::<bool>("FLAG");
+                    for _ in 0..20 {
+                        assert_eq!(settings.get::<bool>("FLAG"), first);
+                    }
+                })
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_explain_winner_matches_memoized_get() {
+        let settings = test_settings();
+        set_value(&settings, raw("FLAG", 0, serde_json::json!(false)));
+        set_value(
+            &settings,
+            RawSetting {
+                filter: [("probability".to_string(), "50".to_string())].into(),
+                ..raw("FLAG", 10, serde_json::json!(true))
+            },
+        );
+
+        for _ in 0..20 {
+            settings
+                .with_request(Request::default(), async {
+                    // explain first: a later get() agrees with it
+                    let winner = settings.explain("FLAG").winner;
+                    let expected = winner == Some(0);
+                    assert_eq!(settings.get::<bool>("FLAG").as_deref(), Some(&expected));
+                    for _ in 0..5 {
+                        assert_eq!(settings.explain("FLAG").winner, winner);
+                    }
+                })
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_memo_is_per_instance_type_and_custom_layer() {
+        let a = test_settings();
+        let b = test_settings();
+        set_value(&a, raw("KEY", 0, serde_json::json!("a")));
+        set_value(&b, raw("KEY", 0, serde_json::json!("b")));
+        set_value(
+            &a,
+            RawSetting {
+                filter: [("context".to_string(), "tenant=acme".to_string())].into(),
+                ..raw("KEY", 10, serde_json::json!("acme"))
+            },
+        );
+
+        a.with_request(Request::default(), async {
+            assert_eq!(a.get::<String>("KEY").as_deref().unwrap(), "a");
+            assert_eq!(b.get::<String>("KEY").as_deref().unwrap(), "b");
+            assert_eq!(
+                a.get::<serde_json::Value>("KEY").as_deref(),
+                Some(&serde_json::json!("a"))
+            );
+
+            let tenant: HashMap<String, String> =
+                [("tenant".to_string(), "acme".to_string())].into();
+            let value = a
+                .with_custom(tenant, async { a.get::<String>("KEY") })
+                .await;
+            assert_eq!(value.as_deref().unwrap(), "acme");
+
+            assert_eq!(a.get::<String>("KEY").as_deref().unwrap(), "a");
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_get_memo_follows_thread_custom_layers() {
+        let settings = test_settings();
+        set_value(&settings, raw("KEY", 0, serde_json::json!("base")));
+        set_value(
+            &settings,
+            RawSetting {
+                filter: [("context".to_string(), "tenant=acme".to_string())].into(),
+                ..raw("KEY", 10, serde_json::json!("acme"))
+            },
+        );
+
+        settings
+            .with_request(Request::default(), async {
+                let before = settings.get::<String>("KEY").unwrap();
+                assert_eq!(before.as_str(), "base");
+
+                {
+                    let tenant = [("tenant".to_string(), "acme".to_string())].into();
+                    let _guard = settings.set_custom(tenant);
+                    assert_eq!(settings.get::<String>("KEY").unwrap().as_str(), "acme");
+                }
+
+                // The outer memo is restored with the layer
+                let after = settings.get::<String>("KEY").unwrap();
+                assert!(Arc::ptr_eq(&before, &after));
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_watch_typed_value() {
+        let settings = test_settings();
+        set_value(&settings, raw("LIMIT", 0, serde_json::json!(10)));
+
+        let mut limit = settings.watch::<u32>("LIMIT").await;
+        let mut missing = settings.watch::<u32>("MISSING").await;
+        assert_eq!(limit.borrow_and_update().as_deref(), Some(&10));
+        assert_eq!(*missing.borrow_and_update(), None);
+
+        set_value(&settings, raw("LIMIT", 0, serde_json::json!(20)));
+        settings.refresh().await.unwrap();
+
+        assert!(limit.has_changed().unwrap());
+        assert_eq!(limit.borrow_and_update().as_deref(), Some(&20));
+        assert!(!missing.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_watch_ignores_caller_context() {
+        let settings = test_settings();
+        set_value(&settings, raw("KEY", 0, serde_json::json!("default")));
+        set_value(
+            &settings,
+            RawSetting {
+                filter: [("context".to_string(), "tenant=acme".to_string())].into(),
+                ..raw("KEY", 10, serde_json::json!("acme"))
+            },
+        );
+
+        let tenant = [("tenant".to_string(), "acme".to_string())].into();
+        let mut receiver = settings
+            .with_custom(tenant, settings.watch::<String>("KEY"))
+            .await;
+        assert_eq!(
+            receiver.borrow_and_update().as_deref().map(String::as_str),
+            Some("default")
+        );
+
+        // Updates use the same context, so nothing changed
+        settings.refresh().await.unwrap();
+        assert!(!receiver.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_watcher_with_custom_context() {
+        let settings = test_settings();
+        let tenant_value = |value: &str| RawSetting {
+            filter: [("context".to_string(), "tenant=acme".to_string())].into(),
+            ..raw("KEY", 10, serde_json::json!(value))
+        };
+        set_value(&settings, raw("KEY", 0, serde_json::json!("default")));
+        set_value(&settings, tenant_value("acme-1"));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| -> Watcher {
+            let seen = seen.clone();
+            Box::new(move |_, new| {
+                seen.lock().unwrap().push((name, new));
+                Box::pin(async {})
+            })
+        };
+        settings.add_watcher("KEY", recorder("default"));
+        settings.add_watcher_with_custom(
+            "KEY",
+            [("tenant".to_string(), "acme".to_string())].into(),
+            recorder("acme"),
+        );
+
+        settings.refresh().await.unwrap();
+        let mut changes = std::mem::take(&mut *seen.lock().unwrap());
+        changes.sort_by_key(|(name, _)| *name);
+        assert_eq!(
+            changes,
+            vec![
+                ("acme", Some(serde_json::json!("acme-1"))),
+                ("default", Some(serde_json::json!("default"))),
+            ]
+        );
+
+        // Only the tenant sees the change
+        set_value(&settings, tenant_value("acme-2"));
+        settings.refresh().await.unwrap();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![("acme", Some(serde_json::json!("acme-2")))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watcher_keeps_value_when_secret_fails() {
+        let settings = test_settings();
+        set_value(&settings, raw("KEY", 0, serde_json::json!("v1")));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        settings.add_watcher(
+            "KEY",
+            Box::new(move |old, new| {
+                seen_clone.lock().unwrap().push((old, new));
+                Box::pin(async {})
+            }),
+        );
+        settings.refresh().await.unwrap();
+
+        // No Vault configured, so the reference can't be resolved
+        let secret = serde_json::json!({"$secret": "secret/data/app:password"});
+        set_value(&settings, raw("KEY", 0, secret));
+        settings.refresh().await.unwrap();
+
+        set_value(&settings, raw("KEY", 0, serde_json::json!("v2")));
+        settings.refresh().await.unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (None, Some(serde_json::json!("v1"))),
+                (Some(serde_json::json!("v1")), Some(serde_json::json!("v2"))),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pattern_watcher_sees_deleted_keys() {
+        let settings = test_settings();
+        set_value(&settings, raw("FEATURE_A", 0, serde_json::json!(true)));
+        set_value(&settings, raw("FEATURE_B", 0, serde_json::json!(true)));
+        set_value(&settings, raw("OTHER", 0, serde_json::json!(1)));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        settings.add_pattern_watcher(
+            "FEATURE_*",
+            Box::new(move |changed| {
+                seen_clone.lock().unwrap().push(changed);
+                Box::pin(async {})
+            }),
+        );
+        settings.refresh().await.unwrap();
+
+        settings.merge_settings(
+            "test",
+            ProviderResponse {
+                settings: vec![],
+                deleted: vec![SettingKey {
+                    key: "FEATURE_B".to_string(),
+                    priority: 0,
+                }],
+                version: "2".to_string(),
+            },
+        );
+        settings.refresh().await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(
+            seen[0],
+            ["FEATURE_A".to_string(), "FEATURE_B".to_string()].into()
+        );
+        assert_eq!(seen[1], ["FEATURE_B".to_string()].into());
+    }
+
+    #[test]
+    fn test_get_validated_runs_validator_once_per_version() {
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+        fn positive(value: &i64) -> Result<(), String> {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            if *value > 0 {
+                Ok(())
+            } else {
+                Err("must be positive".to_string())
+            }
+        }
+
+        let settings = test_settings();
+        set_value(&settings, raw("LIMIT", 0, serde_json::json!(-1)));
+        for _ in 0..3 {
+            assert_eq!(settings.get_validated("LIMIT", "positive", positive), None);
+        }
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+        // A new value is validated again
+        set_value(&settings, raw("LIMIT", 0, serde_json::json!(5)));
+        for _ in 0..3 {
+            assert_eq!(
+                settings
+                    .get_validated("LIMIT", "positive", positive)
+                    .as_deref(),
+                Some(&5)
+            );
+        }
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+
+        // Plain `get` is unaffected by the validator
+        set_value(&settings, raw("LIMIT", 0, serde_json::json!(-2)));
+        assert_eq!(settings.get::<i64>("LIMIT").as_deref(), Some(&-2));
+    }
+
+    #[test]
+    fn test_get_validated_keeps_verdicts_per_validator_id() {
+        let settings = test_settings();
+        set_value(&settings, raw("LIMIT", 0, serde_json::json!(50)));
+
+        // Closures of one type with different bounds must not share a verdict
+        let at_most = |max: i64| {
+            move |value: &i64| {
+                if *value <= max {
+                    Ok(())
+                } else {
+                    Err(format!("must be at most {max}"))
+                }
+            }
+        };
+        assert_eq!(
+            settings
+                .get_validated("LIMIT", "at_most_100", at_most(100))
+                .as_deref(),
+            Some(&50)
+        );
+        assert_eq!(
+            settings.get_validated("LIMIT", "at_most_10", at_most(10)),
+            None
+        );
+        assert_eq!(
+            settings
+                .get_validated("LIMIT", "at_most_100", at_most(100))
+                .as_deref(),
+            Some(&50)
+        );
+    }
+
+    #[test]
+    fn test_is_newer_version() {
+        assert!(is_newer_version("10", "9"));
+        assert!(!is_newer_version("9", "10"));
+        assert!(!is_newer_version("5", "5"));
+        assert!(is_newer_version("b", "a"));
+        assert!(!is_newer_version("a", "a"));
+        assert!(!is_newer_version("", "5"));
+    }
+
     #[test]
     fn test_builder_default() {
         let builder = RuntimeSettingsBuilder::default();
         assert!(builder.mcs_enabled);
         assert!(builder.env_enabled);
         assert!(builder.application.is_empty());
+        assert!(builder.watcher_timeout < builder.refresh_interval / 2);
     }
 
     #[tokio::test]
@@ -35,6 +35,11 @@ pub async fn setup(builder: RuntimeSettingsBuilder) -> Result<(), SettingsError>
         }
     });
 
+    // Receive pushed changes when the provider supports it
+    if settings().push_enabled() {
+        tokio::spawn(settings().run_push_updates());
+    }
+
     Ok(())
 }
 
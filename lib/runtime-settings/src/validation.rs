@@ -0,0 +1,174 @@
+// lib/runtime-settings/src/validation.rs
+//! Per-key validation of setting values at merge time.
+//!
+//! Validators are registered on the builder, either as a Rust type the value
+//! must deserialize into or as a JSON Schema. `merge_settings` rejects values
+//! that fail validation and keeps whatever was loaded before.
+
+use crate::error::SettingsError;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// Validator for a raw setting value
+pub type ValueValidator = Box<dyn Fn(&serde_json::Value) -> Result<(), String> + Send + Sync>;
+
+/// Callback invoked for every rejected setting
+pub type RejectHook = Box<dyn Fn(&SettingRejected) + Send + Sync>;
+
+/// A setting value rejected by its validator
+#[derive(Debug, Clone)]
+pub struct SettingRejected {
+    pub key: String,
+    pub priority: i64,
+    pub provider: String,
+    pub value: serde_json::Value,
+    pub error: String,
+}
+
+/// Validator accepting values that deserialize into `T`
+pub fn type_validator<T>() -> ValueValidator
+where
+    T: DeserializeOwned + 'static,
+{
+    Box::new(|value| T::deserialize(value).map(|_| ()).map_err(|e| e.to_string()))
+}
+
+/// Validator accepting values that match a JSON Schema
+pub fn schema_validator(
+    key: &str,
+    schema: &serde_json::Value,
+) -> Result<ValueValidator, SettingsError> {
+    let validator =
+        jsonschema::validator_for(schema).map_err(|e| SettingsError::InvalidSchema {
+            key: key.to_string(),
+            error: e.to_string(),
+        })?;
+
+    Ok(Box::new(move |value| {
+        validator.validate(value).map_err(|e| e.to_string())
+    }))
+}
+
+/// Registered validators and the reject hook
+#[derive(Default)]
+pub struct SchemaRegistry {
+    validators: HashMap<String, ValueValidator>,
+    on_reject: Option<RejectHook>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a validator for a key, replacing any previous one
+    pub fn register(&mut self, key: impl Into<String>, validator: ValueValidator) {
+        self.validators.insert(key.into(), validator);
+    }
+
+    /// Set the callback invoked for every rejected setting
+    pub fn set_on_reject(&mut self, hook: RejectHook) {
+        self.on_reject = Some(hook);
+    }
+
+    /// Validate a value for a key. Keys without a validator always pass.
+    ///
+    /// On failure the reject is logged and reported to the hook.
+    pub fn check(
+        &self,
+        provider: &str,
+        key: &str,
+        priority: i64,
+        value: &serde_json::Value,
+    ) -> bool {
+        let Some(validator) = self.validators.get(key) else {
+            return true;
+        };
+        let Err(error) = validator(value) else {
+            return true;
+        };
+
+        tracing::warn!(
+            key = %key,
+            priority,
+            provider = %provider,
+            error = %error,
+            "Setting value rejected by schema, keeping previous value"
+        );
+        if let Some(hook) = &self.on_reject {
+            hook(&SettingRejected {
+                key: key.to_string(),
+                priority,
+                provider: provider.to_string(),
+                value: value.clone(),
+                error,
+            });
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Limits {
+        max: u32,
+    }
+
+    #[test]
+    fn test_type_validator() {
+        let validator = type_validator::<Limits>();
+        assert!(validator(&serde_json::json!({"max": 10})).is_ok());
+        assert!(validator(&serde_json::json!({"max": "ten"})).is_err());
+        assert!(validator(&serde_json::json!(42)).is_err());
+    }
+
+    #[test]
+    fn test_schema_validator() {
+        let schema = serde_json::json!({
+            "type": "integer",
+            "minimum": 1,
+            "maximum": 100
+        });
+        let validator = schema_validator("KEY", &schema).unwrap();
+        assert!(validator(&serde_json::json!(50)).is_ok());
+        assert!(validator(&serde_json::json!(500)).is_err());
+        assert!(validator(&serde_json::json!("50")).is_err());
+    }
+
+    #[test]
+    fn test_schema_validator_invalid_schema() {
+        let schema = serde_json::json!({"type": "no-such-type"});
+        assert!(matches!(
+            schema_validator("KEY", &schema),
+            Err(SettingsError::InvalidSchema { key, .. }) if key == "KEY"
+        ));
+    }
+
+    #[test]
+    fn test_registry_reports_rejects() {
+        let rejects = Arc::new(Mutex::new(Vec::new()));
+        let rejects_clone = rejects.clone();
+
+        let mut registry = SchemaRegistry::new();
+        registry.register("LIMIT", type_validator::<u32>());
+        registry.set_on_reject(Box::new(move |r| {
+            rejects_clone.lock().unwrap().push(r.clone());
+        }));
+
+        assert!(registry.check("mcs", "LIMIT", 10, &serde_json::json!(5)));
+        assert!(registry.check("mcs", "OTHER", 10, &serde_json::json!("anything")));
+        assert!(!registry.check("mcs", "LIMIT", 10, &serde_json::json!(-1)));
+
+        let rejects = rejects.lock().unwrap();
+        assert_eq!(rejects.len(), 1);
+        assert_eq!(rejects[0].key, "LIMIT");
+        assert_eq!(rejects[0].provider, "mcs");
+        assert_eq!(rejects[0].value, serde_json::json!(-1));
+    }
+}
@@ -1,11 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::future::Future;
 use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
-use futures::FutureExt;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use tokio::sync::watch;
+
+use crate::context::DynamicContext;
+use crate::error::SettingsError;
+
+/// Default number of watcher callbacks run at once
+pub const DEFAULT_WATCHER_CONCURRENCY: usize = 8;
+
+/// Default time a watcher callback may run before it is cancelled, well below
+/// the default refresh interval since `refresh()` waits for watchers
+pub const DEFAULT_WATCHER_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Unique identifier for a watcher
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -29,28 +42,101 @@ pub type Watcher = Box<
         + Sync,
 >;
 
+/// Async callback for a key pattern, called with the keys that changed
+pub type PatternWatcher =
+    Box<dyn Fn(BTreeSet<String>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Future returned by a watcher callback
+type CallbackFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Callback invocation collected under the locks and made after releasing them
+type PendingCall = Box<dyn FnOnce() -> CallbackFuture + Send>;
+
 struct WatcherEntry {
     id: WatcherId,
-    callback: Watcher,
+    callback: Arc<Watcher>,
+}
+
+/// Watcher for every key matching a pattern
+struct PatternWatcherEntry {
+    id: WatcherId,
+    pattern: String,
+    /// Last seen values of the matching keys
+    snapshot: Mutex<HashMap<String, serde_json::Value>>,
+    callback: Arc<PatternWatcher>,
+}
+
+/// Watcher that evaluates its key under its own context
+struct ContextWatcherEntry {
+    id: WatcherId,
+    key: String,
+    context: DynamicContext,
+    /// Last value seen under `context`
+    last: Mutex<Option<serde_json::Value>>,
+    callback: Arc<Watcher>,
+}
+
+/// Receiving side of a typed subscription
+trait Subscriber: Send + Sync {
+    fn send(&self, key: &str, value: Option<&serde_json::Value>);
+    fn is_closed(&self) -> bool;
+}
+
+struct TypedSubscriber<T> {
+    sender: watch::Sender<Option<Arc<T>>>,
+}
+
+impl<T: DeserializeOwned + Send + Sync + 'static> Subscriber for TypedSubscriber<T> {
+    fn send(&self, key: &str, value: Option<&serde_json::Value>) {
+        self.sender.send_replace(deserialize(key, value));
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+struct SubscriptionEntry {
+    /// Last value sent to the subscriber
+    last: Mutex<Option<serde_json::Value>>,
+    subscriber: Box<dyn Subscriber>,
 }
 
 pub struct WatchersService {
     watchers: RwLock<HashMap<String, Vec<WatcherEntry>>>,
     snapshot: RwLock<HashMap<String, serde_json::Value>>,
+    context_watchers: RwLock<Vec<ContextWatcherEntry>>,
+    pattern_watchers: RwLock<Vec<PatternWatcherEntry>>,
+    subscriptions: RwLock<HashMap<String, Vec<SubscriptionEntry>>>,
+    concurrency: usize,
+    timeout: Duration,
 }
 
 impl WatchersService {
     pub fn new() -> Self {
+        Self::with_limits(DEFAULT_WATCHER_CONCURRENCY, DEFAULT_WATCHER_TIMEOUT)
+    }
+
+    /// Run at most `concurrency` callbacks at once, cancelling each after `timeout`
+    pub fn with_limits(concurrency: usize, timeout: Duration) -> Self {
         Self {
             watchers: RwLock::new(HashMap::new()),
             snapshot: RwLock::new(HashMap::new()),
+            context_watchers: RwLock::new(Vec::new()),
+            pattern_watchers: RwLock::new(Vec::new()),
+            subscriptions: RwLock::new(HashMap::new()),
+            concurrency: concurrency.max(1),
+            timeout,
         }
     }
 
     /// Add a watcher for a key
     pub fn add(&self, key: &str, callback: Watcher) -> WatcherId {
         let id = WatcherId::next();
-        let entry = WatcherEntry { id, callback };
+        let entry = WatcherEntry {
+            id,
+            callback: Arc::new(callback),
+        };
 
         let mut watchers = self.watchers.write().unwrap();
         watchers.entry(key.to_string()).or_default().push(entry);
@@ -58,24 +144,147 @@ impl WatchersService {
         id
     }
 
+    /// Add a watcher for a key as seen under `context`
+    pub fn add_with_context(
+        &self,
+        key: &str,
+        context: DynamicContext,
+        callback: Watcher,
+    ) -> WatcherId {
+        let id = WatcherId::next();
+        let entry = ContextWatcherEntry {
+            id,
+            key: key.to_string(),
+            context,
+            last: Mutex::new(None),
+            callback: Arc::new(callback),
+        };
+
+        self.context_watchers.write().unwrap().push(entry);
+
+        id
+    }
+
+    /// Add a watcher for every key matching `pattern`.
+    ///
+    /// `*` matches any run of characters, so `FEATURE_*` watches all keys
+    /// starting with `FEATURE_`. A pattern without `*` matches only itself.
+    pub fn add_pattern(&self, pattern: &str, callback: PatternWatcher) -> WatcherId {
+        let id = WatcherId::next();
+        let entry = PatternWatcherEntry {
+            id,
+            pattern: pattern.to_string(),
+            snapshot: Mutex::new(HashMap::new()),
+            callback: Arc::new(callback),
+        };
+
+        self.pattern_watchers.write().unwrap().push(entry);
+
+        id
+    }
+
     /// Remove a watcher by ID
     pub fn remove(&self, id: WatcherId) {
         let mut watchers = self.watchers.write().unwrap();
         for entries in watchers.values_mut() {
             entries.retain(|e| e.id != id);
         }
+        drop(watchers);
+
+        let mut context_watchers = self.context_watchers.write().unwrap();
+        context_watchers.retain(|e| e.id != id);
+        drop(context_watchers);
+
+        let mut pattern_watchers = self.pattern_watchers.write().unwrap();
+        pattern_watchers.retain(|e| e.id != id);
+    }
+
+    /// Keys and contexts of watchers added with `add_with_context()`.
+    ///
+    /// Their current values are passed to `check()` by watcher ID.
+    pub fn context_targets(&self) -> Vec<(WatcherId, String, DynamicContext)> {
+        let context_watchers = self.context_watchers.read().unwrap();
+        context_watchers
+            .iter()
+            .map(|e| (e.id, e.key.clone(), e.context.clone()))
+            .collect()
     }
 
-    /// Check for changes and notify watchers
-    #[allow(clippy::type_complexity)]
-    pub async fn check(&self, current_values: &HashMap<String, serde_json::Value>) {
+    /// Whether any watcher, pattern watcher or subscriber follows `key`.
+    ///
+    /// Context watchers are not included, see `context_targets()`.
+    pub fn is_watched(&self, key: &str) -> bool {
+        let watchers = self.watchers.read().unwrap();
+        if watchers.get(key).is_some_and(|entries| !entries.is_empty()) {
+            return true;
+        }
+        drop(watchers);
+
+        if self.subscriptions.read().unwrap().contains_key(key) {
+            return true;
+        }
+
+        let pattern_watchers = self.pattern_watchers.read().unwrap();
+        pattern_watchers
+            .iter()
+            .any(|e| matches_pattern(&e.pattern, key))
+    }
+
+    /// Subscribe to typed values of a key, starting from `current`.
+    ///
+    /// The subscription is dropped with the last receiver.
+    pub fn subscribe<T>(
+        &self,
+        key: &str,
+        current: Option<serde_json::Value>,
+    ) -> watch::Receiver<Option<Arc<T>>>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let (sender, receiver) = watch::channel(deserialize(key, current.as_ref()));
+        let entry = SubscriptionEntry {
+            last: Mutex::new(current),
+            subscriber: Box::new(TypedSubscriber { sender }),
+        };
+
+        let mut subscriptions = self.subscriptions.write().unwrap();
+        subscriptions
+            .entry(key.to_string())
+            .or_default()
+            .push(entry);
+
+        receiver
+    }
+
+    /// Check for changes and notify watchers and subscribers.
+    ///
+    /// `current_values` holds values by key; `context_values` holds values for
+    /// context watchers by watcher ID (see `context_targets()`). Keys in
+    /// `unresolved` couldn't be read this time: their watchers keep the last
+    /// value and are not called.
+    ///
+    /// Callbacks are called after the locks are released and their futures
+    /// are spawned, up to the concurrency limit at once. A callback that panics
+    /// or outlives the timeout is reported and doesn't affect the others; timed
+    /// out tasks are aborted. Returns one error per failed callback.
+    pub async fn check(
+        &self,
+        current_values: &HashMap<String, serde_json::Value>,
+        context_values: &HashMap<WatcherId, serde_json::Value>,
+        unresolved: &HashSet<String>,
+    ) -> Vec<SettingsError> {
+        self.notify_subscribers(current_values, unresolved);
+
         // Collect callbacks to invoke outside the lock
         let callbacks_to_invoke = {
             let watchers = self.watchers.read().unwrap();
             let mut snapshot = self.snapshot.write().unwrap();
-            let mut callbacks: Vec<(String, Pin<Box<dyn Future<Output = ()> + Send>>)> = Vec::new();
+            let mut callbacks: Vec<(String, PendingCall)> = Vec::new();
 
             for (key, entries) in watchers.iter() {
+                if unresolved.contains(key) {
+                    continue;
+                }
                 let old_value = snapshot.get(key).cloned();
                 let new_value = current_values.get(key).cloned();
 
@@ -89,21 +298,100 @@ impl WatchersService {
 
                     // Collect callbacks for later invocation
                     for entry in entries {
-                        let future = (entry.callback)(old_value.clone(), new_value.clone());
-                        callbacks.push((key.clone(), future));
+                        let callback = Arc::clone(&entry.callback);
+                        let (old_value, new_value) = (old_value.clone(), new_value.clone());
+                        let call: PendingCall = Box::new(move || callback(old_value, new_value));
+                        callbacks.push((key.clone(), call));
                     }
                 }
             }
 
+            let context_watchers = self.context_watchers.read().unwrap();
+            for entry in context_watchers.iter() {
+                if unresolved.contains(&entry.key) {
+                    continue;
+                }
+                let new_value = context_values.get(&entry.id).cloned();
+                let mut last = entry.last.lock().unwrap();
+
+                if *last != new_value {
+                    let old_value = std::mem::replace(&mut *last, new_value.clone());
+                    let callback = Arc::clone(&entry.callback);
+                    let call: PendingCall = Box::new(move || callback(old_value, new_value));
+                    callbacks.push((entry.key.clone(), call));
+                }
+            }
+
+            let pattern_watchers = self.pattern_watchers.read().unwrap();
+            for entry in pattern_watchers.iter() {
+                let mut snapshot = entry.snapshot.lock().unwrap();
+                let mut matching: HashMap<String, serde_json::Value> = current_values
+                    .iter()
+                    .filter(|(key, _)| matches_pattern(&entry.pattern, key))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                // Unresolved keys keep their last value
+                for key in unresolved {
+                    if let Some(value) = snapshot.get(key) {
+                        matching.insert(key.clone(), value.clone());
+                    }
+                }
+
+                // Changed, appeared and disappeared keys
+                let changed: BTreeSet<String> = matching
+                    .keys()
+                    .chain(snapshot.keys())
+                    .filter(|key| matching.get(*key) != snapshot.get(*key))
+                    .cloned()
+                    .collect();
+
+                if !changed.is_empty() {
+                    *snapshot = matching;
+                    let callback = Arc::clone(&entry.callback);
+                    let call: PendingCall = Box::new(move || callback(changed));
+                    callbacks.push((entry.pattern.clone(), call));
+                }
+            }
+
             callbacks
         };
 
         // Invoke callbacks outside the lock
-        for (key, future) in callbacks_to_invoke {
-            if let Err(e) = AssertUnwindSafe(future).catch_unwind().await {
-                tracing::error!(key = %key, "Watcher callback panicked: {:?}", e);
+        let calls: Vec<_> = callbacks_to_invoke
+            .into_iter()
+            .map(|(key, call)| run_callback(key, call, self.timeout))
+            .collect();
+        let results: Vec<Option<SettingsError>> = futures::stream::iter(calls)
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+        results.into_iter().flatten().collect()
+    }
+
+    /// Send changed values to subscribers and drop closed subscriptions
+    fn notify_subscribers(
+        &self,
+        current_values: &HashMap<String, serde_json::Value>,
+        unresolved: &HashSet<String>,
+    ) {
+        let mut subscriptions = self.subscriptions.write().unwrap();
+        subscriptions.retain(|key, entries| {
+            entries.retain(|e| !e.subscriber.is_closed());
+            if unresolved.contains(key) {
+                return !entries.is_empty();
             }
-        }
+
+            let new_value = current_values.get(key);
+            for entry in entries.iter() {
+                let mut last = entry.last.lock().unwrap();
+                if last.as_ref() != new_value {
+                    *last = new_value.cloned();
+                    entry.subscriber.send(key, new_value);
+                }
+            }
+
+            !entries.is_empty()
+        });
     }
 
     /// Update snapshot without notifying (for initialization)
@@ -113,6 +401,86 @@ impl WatchersService {
     }
 }
 
+/// Match a key against a pattern where `*` matches any run of characters
+fn matches_pattern(pattern: &str, key: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one part
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = key.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // Last part must match the end of the key
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+
+    // No `*` in the pattern
+    rest.is_empty()
+}
+
+/// Run one watcher callback on its own task, returning a panic or timeout.
+///
+/// The task is aborted on timeout. A callback that blocks its thread can't be
+/// stopped that way, but it no longer holds up `check()`.
+async fn run_callback(key: String, call: PendingCall, timeout: Duration) -> Option<SettingsError> {
+    // Calling the callback runs user code too
+    let future = match std::panic::catch_unwind(AssertUnwindSafe(call)) {
+        Ok(future) => future,
+        Err(panic) => return Some(watcher_panicked(key, panic.as_ref())),
+    };
+
+    let mut task = tokio::spawn(future);
+    match tokio::time::timeout(timeout, &mut task).await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) if e.is_panic() => Some(watcher_panicked(key, e.into_panic().as_ref())),
+        // Cancelled by runtime shutdown
+        Ok(Err(_)) => None,
+        Err(_) => {
+            task.abort();
+            Some(SettingsError::WatcherTimeout { key, timeout })
+        }
+    }
+}
+
+fn watcher_panicked(key: String, panic: &(dyn std::any::Any + Send)) -> SettingsError {
+    SettingsError::WatcherPanicked {
+        key,
+        message: panic_message(panic),
+    }
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Deserialize a watched value; values of the wrong shape are sent as `None`
+fn deserialize<T: DeserializeOwned>(
+    key: &str,
+    value: Option<&serde_json::Value>,
+) -> Option<Arc<T>> {
+    match serde_json::from_value(value?.clone()) {
+        Ok(v) => Some(Arc::new(v)),
+        Err(e) => {
+            tracing::warn!(key = %key, error = %e, "Failed to deserialize watched setting");
+            None
+        }
+    }
+}
+
 impl Default for WatchersService {
     fn default() -> Self {
         Self::new()
@@ -122,8 +490,8 @@ impl Default for WatchersService {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::atomic::{AtomicU32, Ordering};
     use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
 
     #[test]
     fn test_add_and_remove_watcher() {
@@ -176,7 +544,9 @@ mod tests {
         let mut current_values = HashMap::new();
         current_values.insert("KEY".to_string(), serde_json::json!("new_value"));
 
-        service.check(&current_values).await;
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
 
         assert_eq!(called.load(Ordering::SeqCst), 1);
     }
@@ -212,7 +582,9 @@ mod tests {
         let mut current_values = HashMap::new();
         current_values.insert("KEY".to_string(), serde_json::json!("value"));
 
-        service.check(&current_values).await;
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
 
         assert_eq!(counter1.load(Ordering::SeqCst), 1);
         assert_eq!(counter2.load(Ordering::SeqCst), 1);
@@ -249,9 +621,316 @@ mod tests {
         let mut current_values = HashMap::new();
         current_values.insert("KEY".to_string(), serde_json::json!("value"));
 
-        service.check(&current_values).await;
+        let errors = service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
 
         // Second watcher should have executed despite first panicking
         assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            errors.as_slice(),
+            [SettingsError::WatcherPanicked { key, message }]
+                if key == "KEY" && message == "intentional panic"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_context_watcher_uses_own_values() {
+        let service = WatchersService::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+
+        let id = service.add_with_context(
+            "KEY",
+            DynamicContext::default(),
+            Box::new(move |old, new| {
+                seen_clone.lock().unwrap().push((old, new));
+                Box::pin(async {})
+            }),
+        );
+        assert_eq!(service.context_targets()[0].0, id);
+
+        // Values by key don't reach context watchers
+        let current_values = HashMap::from([("KEY".to_string(), serde_json::json!("default"))]);
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+        assert!(seen.lock().unwrap().is_empty());
+
+        let context_values = HashMap::from([(id, serde_json::json!("tenant"))]);
+        service
+            .check(&current_values, &context_values, &HashSet::new())
+            .await;
+        service
+            .check(&current_values, &context_values, &HashSet::new())
+            .await;
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(None, Some(serde_json::json!("tenant")))]
+        );
+
+        service.remove(id);
+        assert!(service.context_targets().is_empty());
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("FEATURE_*", "FEATURE_X"));
+        assert!(matches_pattern("FEATURE_*", "FEATURE_"));
+        assert!(!matches_pattern("FEATURE_*", "MY_FEATURE_X"));
+        assert!(matches_pattern("KAFKA_*_TOPIC", "KAFKA_ORDERS_TOPIC"));
+        assert!(!matches_pattern("KAFKA_*_TOPIC", "KAFKA_ORDERS_TOPIC_DLQ"));
+        assert!(matches_pattern("*_URL", "DB_URL"));
+        assert!(matches_pattern("*", "ANY"));
+        assert!(matches_pattern("A*B*C", "AxxBxxC"));
+        assert!(!matches_pattern("A*B*C", "AxxC"));
+        assert!(matches_pattern("EXACT", "EXACT"));
+        assert!(!matches_pattern("EXACT", "EXACT_NOT"));
+    }
+
+    #[tokio::test]
+    async fn test_pattern_watcher_reports_changed_keys() {
+        let service = WatchersService::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        service.add_pattern(
+            "FEATURE_*",
+            Box::new(move |changed| {
+                seen_clone.lock().unwrap().push(changed);
+                Box::pin(async {})
+            }),
+        );
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<BTreeSet<_>>();
+
+        let mut current_values = HashMap::from([
+            ("FEATURE_A".to_string(), serde_json::json!(true)),
+            ("FEATURE_B".to_string(), serde_json::json!(false)),
+            ("OTHER".to_string(), serde_json::json!(1)),
+        ]);
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+
+        // Nothing changed: not called
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+
+        // One changed, one appeared, one disappeared; OTHER is ignored
+        current_values.insert("FEATURE_A".to_string(), serde_json::json!(false));
+        current_values.insert("FEATURE_C".to_string(), serde_json::json!(true));
+        current_values.remove("FEATURE_B");
+        current_values.insert("OTHER".to_string(), serde_json::json!(2));
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                keys(&["FEATURE_A", "FEATURE_B"]),
+                keys(&["FEATURE_A", "FEATURE_B", "FEATURE_C"]),
+            ]
+        );
+    }
+
+    fn sleeping_watcher(duration: Duration, done: Arc<AtomicU32>) -> Watcher {
+        Box::new(move |_, _| {
+            let done = done.clone();
+            Box::pin(async move {
+                tokio::time::sleep(duration).await;
+                done.fetch_add(1, Ordering::SeqCst);
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_watchers_run_concurrently() {
+        let service = WatchersService::with_limits(4, Duration::from_secs(5));
+        let done = Arc::new(AtomicU32::new(0));
+        for _ in 0..4 {
+            service.add(
+                "KEY",
+                sleeping_watcher(Duration::from_millis(200), done.clone()),
+            );
+        }
+
+        let current_values = HashMap::from([("KEY".to_string(), serde_json::json!(1))]);
+        let started = std::time::Instant::now();
+        let errors = service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+
+        assert!(errors.is_empty());
+        assert_eq!(done.load(Ordering::SeqCst), 4);
+        assert!(started.elapsed() < Duration::from_millis(600));
+    }
+
+    #[tokio::test]
+    async fn test_slow_watcher_times_out() {
+        let service = WatchersService::with_limits(1, Duration::from_millis(50));
+        let slow = Arc::new(AtomicU32::new(0));
+        let fast = Arc::new(AtomicU32::new(0));
+        service.add(
+            "SLOW",
+            sleeping_watcher(Duration::from_secs(10), slow.clone()),
+        );
+        service.add("FAST", sleeping_watcher(Duration::ZERO, fast.clone()));
+
+        let current_values = HashMap::from([
+            ("SLOW".to_string(), serde_json::json!(1)),
+            ("FAST".to_string(), serde_json::json!(1)),
+        ]);
+        let started = std::time::Instant::now();
+        let errors = service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(slow.load(Ordering::SeqCst), 0);
+        assert_eq!(fast.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            errors.as_slice(),
+            [SettingsError::WatcherTimeout { key, .. }] if key == "SLOW"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_panic_building_future_does_not_poison_locks() {
+        let service = WatchersService::new();
+        service.add("KEY", Box::new(|_, _| panic!("panic before the future")));
+
+        let current_values = HashMap::from([("KEY".to_string(), serde_json::json!(1))]);
+        let errors = service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+        assert!(matches!(
+            errors.as_slice(),
+            [SettingsError::WatcherPanicked { message, .. }] if message == "panic before the future"
+        ));
+
+        // The service keeps working
+        let id = service.add("OTHER", Box::new(|_, _| Box::pin(async {})));
+        service.remove(id);
+        let errors = service
+            .check(&HashMap::new(), &HashMap::new(), &HashSet::new())
+            .await;
+        assert_eq!(errors.len(), 1, "only the panicking watcher runs again");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_blocking_watcher_times_out() {
+        let service = WatchersService::with_limits(1, Duration::from_millis(50));
+        service.add(
+            "KEY",
+            Box::new(|_, _| {
+                Box::pin(async {
+                    std::thread::sleep(Duration::from_secs(2));
+                })
+            }),
+        );
+
+        let current_values = HashMap::from([("KEY".to_string(), serde_json::json!(1))]);
+        let started = std::time::Instant::now();
+        let errors = service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(matches!(
+            errors.as_slice(),
+            [SettingsError::WatcherTimeout { key, .. }] if key == "KEY"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_sends_typed_changes() {
+        let service = WatchersService::new();
+        let mut rx = service.subscribe::<u32>("KEY", Some(serde_json::json!(1)));
+        assert_eq!(rx.borrow_and_update().as_deref(), Some(&1));
+
+        // Unchanged value is not sent again
+        let mut current_values = HashMap::new();
+        current_values.insert("KEY".to_string(), serde_json::json!(1));
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+        assert!(!rx.has_changed().unwrap());
+
+        current_values.insert("KEY".to_string(), serde_json::json!(2));
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(rx.borrow_and_update().as_deref(), Some(&2));
+
+        // Wrong shape and removal are both `None`
+        current_values.insert("KEY".to_string(), serde_json::json!("two"));
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+        assert_eq!(*rx.borrow_and_update(), None);
+
+        current_values.insert("KEY".to_string(), serde_json::json!(3));
+        service
+            .check(&current_values, &HashMap::new(), &HashSet::new())
+            .await;
+        assert_eq!(rx.borrow_and_update().as_deref(), Some(&3));
+
+        service
+            .check(&HashMap::new(), &HashMap::new(), &HashSet::new())
+            .await;
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(*rx.borrow_and_update(), None);
+    }
+
+    #[tokio::test]
+    async fn test_subscription_dropped_with_receiver() {
+        let service = WatchersService::new();
+        let rx = service.subscribe::<String>("KEY", None);
+        let rx2 = rx.clone();
+        drop(rx);
+
+        service
+            .check(&HashMap::new(), &HashMap::new(), &HashSet::new())
+            .await;
+        assert!(service.subscriptions.read().unwrap().contains_key("KEY"));
+
+        drop(rx2);
+        service
+            .check(&HashMap::new(), &HashMap::new(), &HashSet::new())
+            .await;
+        assert!(service.subscriptions.read().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unresolved_keys_keep_last_value() {
+        let service = WatchersService::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        service.add(
+            "KEY",
+            Box::new(move |old, new| {
+                seen_clone.lock().unwrap().push((old, new));
+                Box::pin(async {})
+            }),
+        );
+        assert!(service.is_watched("KEY"));
+        assert!(!service.is_watched("OTHER"));
+
+        let v1: HashMap<String, serde_json::Value> =
+            [("KEY".to_string(), serde_json::json!(1))].into();
+        service.check(&v1, &HashMap::new(), &HashSet::new()).await;
+
+        let unresolved: HashSet<String> = ["KEY".to_string()].into();
+        service
+            .check(&HashMap::new(), &HashMap::new(), &unresolved)
+            .await;
+        service.check(&v1, &HashMap::new(), &HashSet::new()).await;
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(None, Some(serde_json::json!(1)))]
+        );
     }
 }
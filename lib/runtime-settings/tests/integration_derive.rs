@@ -0,0 +1,125 @@
+//! Integration tests for `#[derive(RuntimeSettings)]` typed accessors.
+
+use runtime_settings::RuntimeSettings;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+#[derive(RuntimeSettings)]
+#[allow(dead_code)]
+pub struct FeatureSettings {
+    /// Maximum pool size
+    #[setting(default = 10, validate = validate_pool_size)]
+    pub max_connections: u32,
+    #[setting(key = "FEATURE_X_ENABLED", default = false)]
+    pub feature_x: bool,
+    #[setting(default = String::from("hello"))]
+    pub greeting: String,
+    pub retries: u8,
+    pub endpoint: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+fn validate_pool_size(value: &u32) -> Result<(), String> {
+    if (1..=100).contains(value) {
+        Ok(())
+    } else {
+        Err(format!("pool size {value} out of range 1..=100"))
+    }
+}
+
+async fn settings_from_file(content: &str) -> (TempDir, runtime_settings::RuntimeSettings) {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("settings.json");
+    std::fs::write(&path, content).unwrap();
+
+    let settings = runtime_settings::RuntimeSettings::builder()
+        .application("test-app")
+        .mcs_enabled(false)
+        .env_enabled(false)
+        .file_path(path.to_str().unwrap())
+        .build()
+        .unwrap();
+    settings.init().await.unwrap();
+
+    (dir, settings)
+}
+
+#[test]
+fn test_derive_keys() {
+    assert_eq!(
+        FeatureSettings::KEYS,
+        &[
+            "MAX_CONNECTIONS",
+            "FEATURE_X_ENABLED",
+            "GREETING",
+            "RETRIES",
+            "ENDPOINT",
+            "TAGS"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_derive_reads_values() {
+    let (_dir, settings) = settings_from_file(
+        r#"[
+            {"key": "MAX_CONNECTIONS", "priority": 0, "value": 42},
+            {"key": "FEATURE_X_ENABLED", "priority": 0, "value": true},
+            {"key": "GREETING", "priority": 0, "value": "hi"},
+            {"key": "ENDPOINT", "priority": 0, "value": "http://localhost"}
+        ]"#,
+    )
+    .await;
+
+    let feature = FeatureSettings::accessor(&settings);
+    assert_eq!(*feature.max_connections(), 42);
+    assert!(*feature.feature_x());
+    assert_eq!(feature.greeting().as_str(), "hi");
+    assert_eq!(
+        feature.endpoint().as_deref().map(String::as_str),
+        Some("http://localhost")
+    );
+}
+
+#[tokio::test]
+async fn test_derive_falls_back_to_defaults() {
+    let (_dir, settings) = settings_from_file("[]").await;
+
+    let feature = FeatureSettings::accessor(&settings);
+    assert_eq!(*feature.max_connections(), 10);
+    assert!(!*feature.feature_x());
+    assert_eq!(feature.greeting().as_str(), "hello");
+    assert_eq!(*feature.retries(), 0);
+    assert!(feature.endpoint().is_none());
+    assert!(feature.tags().is_none());
+}
+
+#[tokio::test]
+async fn test_derive_rejected_or_mistyped_values_use_default() {
+    let (_dir, settings) = settings_from_file(
+        r#"[
+            {"key": "MAX_CONNECTIONS", "priority": 0, "value": 1000},
+            {"key": "RETRIES", "priority": 0, "value": "three"}
+        ]"#,
+    )
+    .await;
+
+    let feature = FeatureSettings::accessor(&settings);
+    assert_eq!(*feature.max_connections(), 10);
+    assert_eq!(*feature.retries(), 0);
+}
+
+#[tokio::test]
+async fn test_derive_reuses_value_cache() {
+    let (_dir, settings) =
+        settings_from_file(r#"[{"key": "TAGS", "priority": 0, "value": ["a", "b"]}]"#).await;
+
+    let feature = FeatureSettings::accessor(&settings);
+    let first = feature.tags().unwrap();
+    let second = feature.tags().unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+
+    // Same cache entry as an untyped get() of the same type
+    let direct: Arc<Vec<String>> = settings.get("TAGS").unwrap();
+    assert!(Arc::ptr_eq(&first, &direct));
+}
@@ -6,6 +6,10 @@
 
 use runtime_settings::context::{CustomContext, DynamicContext, Request, StaticContext};
 use runtime_settings::entities::{RawSetting, Setting};
+use runtime_settings::filters::{
+    CompiledDynamicFilter, CompiledStaticFilter, FilterRegistry, is_static_filter,
+};
+use runtime_settings::{FixedClock, RuntimeSettings, SettingsError};
 use std::collections::HashMap;
 
 /// Create a RawSetting for testing purposes.
@@ -73,6 +77,7 @@ fn request_ctx(path: &str, email: Option<&str>, ip: Option<&str>) -> DynamicCont
             method: "GET".to_string(),
             path: path.to_string(),
             headers,
+            ..Default::default()
         }),
         custom: CustomContext::new(),
     }
@@ -91,7 +96,7 @@ mod tests {
             &[("application", "test-app")],
             serde_json::json!("value"),
         );
-        let setting = Setting::compile(raw).expect("should compile");
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
         assert_eq!(setting.key, "TEST_KEY");
         assert_eq!(setting.priority, 100);
 
@@ -117,7 +122,7 @@ mod tests {
             &[("application", "my-app")],
             serde_json::json!("value"),
         );
-        let setting = Setting::compile(raw).expect("should compile");
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
 
         // Should match when application is "my-app"
         let ctx_match = static_ctx("my-app", "server1", None);
@@ -145,7 +150,7 @@ mod tests {
             &[("mcs_run_env", "PROD")],
             serde_json::json!("value"),
         );
-        let setting = Setting::compile(raw).expect("should compile");
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
 
         // Should return FALSE when mcs_run_env is None (the fix)
         let ctx_none = static_ctx("my-app", "server1", None);
@@ -178,7 +183,7 @@ mod tests {
             &[("application", "my-app"), ("server", "server-1")],
             serde_json::json!("value"),
         );
-        let setting = Setting::compile(raw).expect("should compile");
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
 
         // Should match when BOTH application and server match
         let ctx_both_match = static_ctx("my-app", "server-1", None);
@@ -213,7 +218,7 @@ mod tests {
             &[("url-path", "/api/.*")],
             serde_json::json!("api_value"),
         );
-        let setting = Setting::compile(raw).expect("should compile");
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
 
         // Should match /api/users
         let ctx_users = request_ctx("/api/users", None, None);
@@ -246,7 +251,7 @@ mod tests {
             &[("email", ".*@example\\.com")],
             serde_json::json!("email_value"),
         );
-        let setting = Setting::compile(raw).expect("should compile");
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
 
         // Should match user@example.com
         let ctx_user = request_ctx("/", Some("user@example.com"), None);
@@ -287,7 +292,7 @@ mod tests {
             &[("ip", "192\\.168\\..*")],
             serde_json::json!("ip_value"),
         );
-        let setting = Setting::compile(raw).expect("should compile");
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
 
         // Should match 192.168.1.1
         let ctx_match1 = request_ctx("/", None, Some("192.168.1.1"));
@@ -311,6 +316,111 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ip_cidr_filter() {
+        // Create setting with ip-cidr filter (IPv4 and IPv6 blocks)
+        let raw = raw_setting(
+            "IP_CIDR_SETTING",
+            100,
+            &[("ip-cidr", "10.0.0.0/8, 192.168.1.0/24, fd00::/8")],
+            serde_json::json!("internal"),
+        );
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
+
+        for ip in ["10.255.0.1", "192.168.1.7", "fd12::1"] {
+            let ctx = request_ctx("/", None, Some(ip));
+            assert!(setting.check_dynamic_filters(&ctx), "Should match {ip}");
+        }
+        for ip in ["192.168.2.1", "8.8.8.8", "2001:db8::1"] {
+            let ctx = request_ctx("/", None, Some(ip));
+            assert!(
+                !setting.check_dynamic_filters(&ctx),
+                "Should not match {ip}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ip_cidr_filter_rejects_bad_block() {
+        // One bad block fails the whole setting rather than being dropped
+        for pattern in ["10.0.0.0/33", "10.0.0.0/8,not-a-cidr"] {
+            let raw = raw_setting(
+                "CIDR_SETTING",
+                100,
+                &[("ip-cidr", pattern)],
+                serde_json::json!(1),
+            );
+            assert!(
+                Setting::compile(raw, &FilterRegistry::new()).is_err(),
+                "{pattern} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rollout_filter_is_sticky_per_key() {
+        // Same 50% rollout on two keys, bucketed on the email header
+        let compile = |key: &str| {
+            Setting::compile(
+                raw_setting(
+                    key,
+                    100,
+                    &[("rollout", "email=50")],
+                    serde_json::json!(true),
+                ),
+                &FilterRegistry::new(),
+            )
+            .expect("should compile")
+        };
+        let feature_a = compile("FEATURE_A");
+        let feature_b = compile("FEATURE_B");
+
+        let mut differs = false;
+        for i in 0..100 {
+            let email = format!("user{i}@example.com");
+            let ctx = request_ctx("/", Some(&email), None);
+
+            // A user always gets the same answer for a key
+            let a = feature_a.check_dynamic_filters(&ctx);
+            assert_eq!(a, feature_a.check_dynamic_filters(&ctx));
+
+            differs |= a != feature_b.check_dynamic_filters(&ctx);
+        }
+        assert!(differs, "Rollouts of different keys should be independent");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_rollout_does_not_match() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"key": "FEATURE", "priority": 0, "value": "off"},
+                {"key": "FEATURE", "priority": 10, "filter": {"rollout": "email=150"}, "value": "over"},
+                {"key": "FEATURE", "priority": 20, "filter": {"rollout": "email>50"}, "value": "syntax"}
+            ]"#,
+        )
+        .unwrap();
+
+        let settings = RuntimeSettings::builder()
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .file_path(path.to_str().unwrap())
+            .build()
+            .unwrap();
+        settings.init().await.unwrap();
+
+        // Both rollouts fail to compile, so their settings are skipped
+        let headers = [("x-real-email".to_string(), "user@example.com".to_string())].into();
+        let value = settings
+            .with_request(Request::new("GET", "/", headers), async {
+                settings.get::<String>("FEATURE")
+            })
+            .await;
+        assert_eq!(value.as_deref().map(String::as_str), Some("off"));
+    }
+
     #[test]
     fn test_mixed_static_and_dynamic_filters() {
         // Create setting with both static (application) and dynamic (url-path) filters
@@ -320,7 +430,7 @@ mod tests {
             &[("application", "my-app"), ("url-path", "/api/.*")],
             serde_json::json!("mixed_value"),
         );
-        let setting = Setting::compile(raw).expect("should compile");
+        let setting = Setting::compile(raw, &FilterRegistry::new()).expect("should compile");
 
         // Create contexts for testing
         let static_ctx_match = static_ctx("my-app", "server1", None);
@@ -355,4 +465,168 @@ mod tests {
         // Both filters work independently - for a setting to fully match,
         // both static AND dynamic filters must pass
     }
+
+    /// Static filter matching servers whose name starts with the pattern
+    struct DatacenterFilter(String);
+
+    impl CompiledStaticFilter for DatacenterFilter {
+        fn check(&self, ctx: &StaticContext) -> bool {
+            ctx.server.starts_with(&self.0)
+        }
+    }
+
+    /// Dynamic filter matching the `tenant` custom context value
+    struct TenantFilter(String);
+
+    impl CompiledDynamicFilter for TenantFilter {
+        fn check(&self, ctx: &DynamicContext) -> bool {
+            ctx.custom.get("tenant") == Some(self.0.as_str())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_filters_registered_on_builder() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"key": "LIMIT", "priority": 0, "value": 1},
+                {"key": "LIMIT", "priority": 10, "filter": {"tenant": "acme"}, "value": 2},
+                {"key": "LIMIT", "priority": 20, "filter": {"datacenter": "eu-"}, "value": 3},
+                {"key": "LIMIT", "priority": 30, "filter": {"not": {"tenant": "acme"}}, "value": 4}
+            ]"#,
+        )
+        .unwrap();
+
+        let settings = RuntimeSettings::builder()
+            .application("test-app")
+            .server("us-1")
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .file_path(path.to_str().unwrap())
+            .register_static_filter("datacenter", |pattern| {
+                Ok(Box::new(DatacenterFilter(pattern.to_string())))
+            })
+            .register_dynamic_filter("tenant", |pattern| {
+                Ok(Box::new(TenantFilter(pattern.to_string())))
+            })
+            .build()
+            .unwrap();
+        settings.init().await.unwrap();
+
+        assert!(is_static_filter("datacenter", settings.filters()));
+        assert!(!is_static_filter("tenant", settings.filters()));
+
+        // Filters stay on the instance that registered them
+        let other = RuntimeSettings::builder()
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .build()
+            .unwrap();
+        assert!(!is_static_filter("datacenter", other.filters()));
+
+        let acme: HashMap<String, String> = [("tenant".to_string(), "acme".to_string())].into();
+        let value = settings
+            .with_custom(acme, async { settings.get::<i64>("LIMIT") })
+            .await;
+        assert_eq!(value.as_deref(), Some(&2));
+
+        // The datacenter setting was dropped at load time, `not` picks up the rest
+        assert_eq!(settings.get::<i64>("LIMIT").as_deref(), Some(&4));
+    }
+
+    #[test]
+    fn test_custom_filter_cannot_override_builtin() {
+        let result = RuntimeSettings::builder()
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .register_dynamic_filter("host", |pattern| {
+                Ok(Box::new(TenantFilter(pattern.to_string())))
+            })
+            .build();
+        assert!(matches!(
+            result,
+            Err(SettingsError::ReservedFilterName { name }) if name == "host"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_builder_clock_drives_time_filters() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"key": "BANNER", "priority": 0, "value": "default"},
+                {"key": "BANNER", "priority": 10, "filter": {"active-from": "2030-01-01T00:00:00Z"}, "value": "launch"}
+            ]"#,
+        )
+        .unwrap();
+
+        let build = |now: &str| {
+            let now = chrono::DateTime::parse_from_rfc3339(now).unwrap().to_utc();
+            RuntimeSettings::builder()
+                .mcs_enabled(false)
+                .env_enabled(false)
+                .file_path(path.to_str().unwrap())
+                .clock(std::sync::Arc::new(FixedClock(now)))
+                .build()
+                .unwrap()
+        };
+
+        let before = build("2029-12-31T23:00:00Z");
+        before.init().await.unwrap();
+        assert_eq!(
+            before
+                .get::<String>("BANNER")
+                .as_deref()
+                .map(String::as_str),
+            Some("default")
+        );
+
+        let after = build("2030-01-01T01:00:00Z");
+        after.init().await.unwrap();
+        assert_eq!(
+            after.get::<String>("BANNER").as_deref().map(String::as_str),
+            Some("launch")
+        );
+
+        // `explain` reports the injected time
+        let explanation = after.explain("BANNER");
+        let filter = &explanation.candidates[0].filters[0];
+        assert_eq!(filter.input.as_deref(), Some("2030-01-01T01:00:00Z"));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_time_filters_fail_closed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"key": "BANNER", "priority": 0, "value": "default"},
+                {"key": "BANNER", "priority": 10, "filter": {"active-until": "next friday"}, "value": "until"},
+                {"key": "BANNER", "priority": 20, "filter": {"schedule": "0 9-17 * *"}, "value": "schedule"}
+            ]"#,
+        )
+        .unwrap();
+
+        let settings = RuntimeSettings::builder()
+            .mcs_enabled(false)
+            .env_enabled(false)
+            .file_path(path.to_str().unwrap())
+            .build()
+            .unwrap();
+        settings.init().await.unwrap();
+
+        // Neither setting stays active forever
+        assert_eq!(
+            settings
+                .get::<String>("BANNER")
+                .as_deref()
+                .map(String::as_str),
+            Some("default")
+        );
+    }
 }
@@ -1,6 +1,9 @@
 // lib/runtime-settings/tests/integration_mcs.rs
 
+use runtime_settings::RuntimeSettings;
 use runtime_settings::providers::{McsProvider, SettingsProvider};
+use std::sync::Arc;
+use std::time::Duration;
 use wiremock::matchers::{method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -144,3 +147,275 @@ async fn test_mcs_provider_handles_error_response() {
         error_string
     );
 }
+
+#[tokio::test]
+async fn test_init_writes_mcs_cache_and_uses_it_when_mcs_is_down() {
+    let cache_dir = tempfile::TempDir::new().unwrap();
+    let cache_path = cache_dir.path().join("mcs-cache.json");
+
+    // First start: MCS is up, the cache gets written
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v3/get-runtime-settings/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "settings": [
+                {"key": "CACHED_KEY", "priority": 100, "filter": {}, "value": "from-mcs"}
+            ],
+            "deleted": [],
+            "version": "7"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let settings = RuntimeSettings::builder()
+        .application("test-app")
+        .env_enabled(false)
+        .mcs_base_url(mock_server.uri())
+        .mcs_cache_path(cache_path.to_string_lossy())
+        .build()
+        .unwrap();
+    settings.init().await.unwrap();
+    assert!(cache_path.exists());
+
+    // Second start: MCS is down, settings come from the cache
+    let failing_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v3/get-runtime-settings/"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&failing_server)
+        .await;
+
+    let settings = RuntimeSettings::builder()
+        .application("test-app")
+        .env_enabled(false)
+        .mcs_base_url(failing_server.uri())
+        .mcs_cache_path(cache_path.to_string_lossy())
+        .build()
+        .unwrap();
+    settings.init().await.unwrap();
+
+    let value: Option<Arc<String>> = settings.get("CACHED_KEY");
+    assert_eq!(value.as_deref().map(String::as_str), Some("from-mcs"));
+}
+
+#[tokio::test]
+async fn test_refresh_after_cache_fallback_requests_cached_version() {
+    let cache_dir = tempfile::TempDir::new().unwrap();
+    let cache_path = cache_dir.path().join("mcs-cache.json");
+    std::fs::write(
+        &cache_path,
+        serde_json::json!({
+            "settings": [{"key": "KEY", "priority": 100, "filter": {}, "value": 1}],
+            "deleted": [],
+            "version": "7"
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v3/get-runtime-settings/"))
+        .and(query_param("version", ""))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v3/get-runtime-settings/"))
+        .and(query_param("version", "7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "settings": [{"key": "KEY", "priority": 100, "filter": {}, "value": 2}],
+            "deleted": [],
+            "version": "8"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let settings = RuntimeSettings::builder()
+        .application("test-app")
+        .env_enabled(false)
+        .mcs_base_url(mock_server.uri())
+        .mcs_cache_path(cache_path.to_string_lossy())
+        .build()
+        .unwrap();
+    settings.init().await.unwrap();
+    assert_eq!(settings.get::<i64>("KEY").as_deref(), Some(&1));
+
+    settings.refresh().await.unwrap();
+    assert_eq!(settings.get::<i64>("KEY").as_deref(), Some(&2));
+
+    // Cache was rewritten with the new version
+    let cached: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&cache_path).unwrap()).unwrap();
+    assert_eq!(cached["version"], "8");
+}
+
+#[tokio::test]
+async fn test_mcs_provider_long_poll_returns_delta() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v3/watch-runtime-settings/"))
+        .and(query_param("runtime", "rust"))
+        .and(query_param("version", "5"))
+        .and(query_param("timeout", "30"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "settings": [{"key": "FLAG", "priority": 100, "filter": {}, "value": true}],
+            "deleted": [],
+            "version": "6"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let provider = McsProvider::new(mock_server.uri(), "test-app".to_string(), None)
+        .with_long_poll(Duration::from_secs(30));
+    let result = provider.wait_for_changes("5").await.unwrap().unwrap();
+
+    assert_eq!(result.version, "6");
+    assert_eq!(result.settings[0].key, "FLAG");
+}
+
+#[tokio::test]
+async fn test_mcs_provider_long_poll_not_modified() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v3/watch-runtime-settings/"))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let provider = McsProvider::new(mock_server.uri(), "test-app".to_string(), None)
+        .with_long_poll(Duration::from_secs(30));
+    let result = provider.wait_for_changes("5").await.unwrap();
+
+    assert!(result.is_none());
+}
+
+/// Mounts the initial full load answering with `FLAG = false` at version 1
+async fn mount_initial_load(mock_server: &MockServer, expected_calls: u64) {
+    Mock::given(method("GET"))
+        .and(path("/v3/get-runtime-settings/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "settings": [{"key": "FLAG", "priority": 100, "filter": {}, "value": false}],
+            "deleted": [],
+            "version": "1"
+        })))
+        .expect(expected_calls)
+        .mount(mock_server)
+        .await;
+}
+
+fn push_settings(mock_server: &MockServer) -> RuntimeSettings {
+    RuntimeSettings::builder()
+        .application("test-app")
+        .env_enabled(false)
+        .mcs_base_url(mock_server.uri())
+        .mcs_long_poll(Duration::from_secs(30))
+        .refresh_interval(Duration::from_millis(50))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_push_updates_apply_delta_without_polling() {
+    let mock_server = MockServer::start().await;
+    // Only the initial load: refresh() must not poll while push is healthy
+    mount_initial_load(&mock_server, 1).await;
+
+    Mock::given(method("GET"))
+        .and(path("/v3/watch-runtime-settings/"))
+        .and(query_param("version", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "settings": [{"key": "FLAG", "priority": 100, "filter": {}, "value": true}],
+            "deleted": [],
+            "version": "2"
+        })))
+        .mount(&mock_server)
+        .await;
+    // No further changes: MCS holds the request
+    Mock::given(method("GET"))
+        .and(path("/v3/watch-runtime-settings/"))
+        .and(query_param("version", "2"))
+        .respond_with(ResponseTemplate::new(304).set_delay(Duration::from_millis(100)))
+        .mount(&mock_server)
+        .await;
+
+    let settings = push_settings(&mock_server);
+    assert!(settings.push_enabled());
+    settings.init().await.unwrap();
+    assert_eq!(settings.get::<bool>("FLAG").as_deref(), Some(&false));
+
+    let _ = tokio::time::timeout(Duration::from_millis(300), settings.run_push_updates()).await;
+    assert_eq!(settings.get::<bool>("FLAG").as_deref(), Some(&true));
+
+    settings.refresh().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_push_falls_back_to_polling_when_stream_drops() {
+    let mock_server = MockServer::start().await;
+    // Initial load plus one polling refresh
+    mount_initial_load(&mock_server, 2).await;
+
+    Mock::given(method("GET"))
+        .and(path("/v3/watch-runtime-settings/"))
+        .respond_with(ResponseTemplate::new(502))
+        .mount(&mock_server)
+        .await;
+
+    let settings = push_settings(&mock_server);
+    settings.init().await.unwrap();
+
+    let _ = tokio::time::timeout(Duration::from_millis(120), settings.run_push_updates()).await;
+
+    settings.refresh().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_push_backs_off_when_nothing_changes() {
+    let mock_server = MockServer::start().await;
+    mount_initial_load(&mock_server, 1).await;
+
+    // A server that answers right away instead of holding the request
+    Mock::given(method("GET"))
+        .and(path("/v3/watch-runtime-settings/"))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1..=2)
+        .mount(&mock_server)
+        .await;
+
+    let settings = push_settings(&mock_server);
+    settings.init().await.unwrap();
+
+    let _ = tokio::time::timeout(Duration::from_millis(300), settings.run_push_updates()).await;
+}
+
+#[tokio::test]
+async fn test_push_drops_delta_not_newer_than_current_version() {
+    let mock_server = MockServer::start().await;
+    mount_initial_load(&mock_server, 1).await;
+
+    // A delta already superseded by a concurrent poll
+    Mock::given(method("GET"))
+        .and(path("/v3/watch-runtime-settings/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "settings": [{"key": "FLAG", "priority": 100, "filter": {}, "value": true}],
+            "deleted": [],
+            "version": "1"
+        })))
+        .expect(1..=2)
+        .mount(&mock_server)
+        .await;
+
+    let settings = push_settings(&mock_server);
+    settings.init().await.unwrap();
+
+    let _ = tokio::time::timeout(Duration::from_millis(300), settings.run_push_updates()).await;
+    assert_eq!(settings.get::<bool>("FLAG").as_deref(), Some(&false));
+}
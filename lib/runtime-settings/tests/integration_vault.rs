@@ -1,8 +1,10 @@
 // lib/runtime-settings/tests/integration_vault.rs
 
-use runtime_settings::SecretsService;
+use runtime_settings::{RuntimeSettings, Secret, SecretsPrefetch, SecretsService, SettingsError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use vault_client::VaultClient;
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{body_partial_json, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 async fn mock_vault_client(mock_uri: &str, token: &str) -> VaultClient {
@@ -16,11 +18,21 @@ async fn mock_vault_client(mock_uri: &str, token: &str) -> VaultClient {
 
 /// Helper to create a Vault KV2 response in the expected format.
 fn vault_kv2_response(data: serde_json::Value) -> serde_json::Value {
+    vault_leased_response(data, "", 0, false)
+}
+
+/// Helper to create a Vault KV2 response carrying a lease.
+fn vault_leased_response(
+    data: serde_json::Value,
+    lease_id: &str,
+    lease_duration: u64,
+    renewable: bool,
+) -> serde_json::Value {
     serde_json::json!({
         "request_id": "test-request-id",
-        "lease_id": "",
-        "renewable": false,
-        "lease_duration": 0,
+        "lease_id": lease_id,
+        "renewable": renewable,
+        "lease_duration": lease_duration,
         "data": {
             "data": data,
             "metadata": {
@@ -77,6 +89,30 @@ async fn test_vault_get_secret() {
     assert_eq!(username, serde_json::json!("admin"));
 }
 
+#[tokio::test]
+async fn test_vault_nested_kv_mount() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/team/kv/data/app"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(vault_kv2_response(serde_json::json!({"token": "t1"}))),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let secrets_service = SecretsService::new(client);
+
+    let token = secrets_service
+        .get("team/kv/data/app", "token")
+        .await
+        .unwrap();
+    assert_eq!(token, serde_json::json!("t1"));
+}
+
 #[tokio::test]
 async fn test_vault_secret_caching() {
     // Start mock server
@@ -134,7 +170,9 @@ async fn test_vault_secret_not_found() {
     let secrets_service = SecretsService::new(client);
 
     // Verify get() returns error
-    let result = secrets_service.get("secret/data/nonexistent/path", "key").await;
+    let result = secrets_service
+        .get("secret/data/nonexistent/path", "key")
+        .await;
 
     assert!(result.is_err(), "should return error for 404");
     let err = result.unwrap_err();
@@ -167,7 +205,9 @@ async fn test_vault_key_not_found_in_secret() {
     let secrets_service = SecretsService::new(client);
 
     // Try to get non-existent key
-    let result = secrets_service.get("secret/data/app/config", "nonexistent_key").await;
+    let result = secrets_service
+        .get("secret/data/app/config", "nonexistent_key")
+        .await;
 
     // Verify get() returns error
     assert!(result.is_err(), "should return error for missing key");
@@ -202,3 +242,680 @@ async fn test_secrets_without_vault() {
         err_string
     );
 }
+
+#[tokio::test]
+async fn test_vault_lease_renewed_at_threshold() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/leased"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_leased_response(
+                serde_json::json!({"password": "p1"}),
+                "secret/leased/abc",
+                1,
+                true,
+            )),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("PUT"))
+        .and(path("/v1/sys/leases/renew"))
+        .and(body_partial_json(
+            serde_json::json!({"lease_id": "secret/leased/abc"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "lease_id": "secret/leased/abc",
+            "renewable": true,
+            "lease_duration": 60
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let secrets_service = SecretsService::new(client);
+    secrets_service
+        .get("secret/data/leased", "password")
+        .await
+        .unwrap();
+
+    // Not due yet: less than 75% of the TTL elapsed
+    secrets_service.refresh().await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(800)).await;
+    secrets_service.refresh().await.unwrap();
+    assert_eq!(secrets_service.version(), 0, "renewal keeps the value");
+
+    // Renewed lease is 60s, so the next refresh does nothing
+    secrets_service.refresh().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_vault_lease_renewal_failure_rereads_secret() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/leased"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_leased_response(
+                serde_json::json!({"password": "p1"}),
+                "secret/leased/abc",
+                1,
+                true,
+            )),
+        )
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/leased"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_leased_response(
+                serde_json::json!({"password": "p2"}),
+                "secret/leased/def",
+                60,
+                true,
+            )),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("PUT"))
+        .and(path("/v1/sys/leases/renew"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "errors": ["lease not found"]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let secrets_service = SecretsService::new(client);
+    let value = secrets_service
+        .get("secret/data/leased", "password")
+        .await
+        .unwrap();
+    assert_eq!(value, serde_json::json!("p1"));
+
+    tokio::time::sleep(Duration::from_millis(800)).await;
+    secrets_service.refresh().await.unwrap();
+
+    assert_eq!(
+        secrets_service.version(),
+        1,
+        "changed value bumps the version"
+    );
+    let value = secrets_service
+        .get("secret/data/leased", "password")
+        .await
+        .unwrap();
+    assert_eq!(value, serde_json::json!("p2"));
+}
+
+#[tokio::test]
+async fn test_vault_lease_near_max_ttl_rereads_secret() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/leased"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_leased_response(
+                serde_json::json!({"password": "p1"}),
+                "secret/leased/abc",
+                2,
+                true,
+            )),
+        )
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/leased"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_leased_response(
+                serde_json::json!({"password": "p2"}),
+                "secret/leased/def",
+                60,
+                true,
+            )),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Near its max TTL Vault grants less than the lease had before
+    Mock::given(method("PUT"))
+        .and(path("/v1/sys/leases/renew"))
+        .and(body_partial_json(
+            serde_json::json!({"lease_id": "secret/leased/abc"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "lease_id": "secret/leased/abc",
+            "renewable": true,
+            "lease_duration": 1
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let secrets_service = SecretsService::new(client).with_refresh_interval(Duration::from_secs(1));
+    secrets_service
+        .get("secret/data/leased", "password")
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(1600)).await;
+    secrets_service.refresh().await.unwrap();
+
+    assert_eq!(secrets_service.version(), 1, "capped lease is re-read");
+    let value = secrets_service
+        .get("secret/data/leased", "password")
+        .await
+        .unwrap();
+    assert_eq!(value, serde_json::json!("p2"));
+
+    // The new lease is 60s, so the next refresh does nothing
+    secrets_service.refresh().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_vault_non_renewable_lease_reread_unchanged() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/leased"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_leased_response(
+                serde_json::json!({"password": "p1"}),
+                "",
+                1,
+                false,
+            )),
+        )
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let secrets_service = SecretsService::new(client);
+    secrets_service
+        .get("secret/data/leased", "password")
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(800)).await;
+    secrets_service.refresh().await.unwrap();
+
+    assert_eq!(
+        secrets_service.version(),
+        0,
+        "unchanged value keeps the version"
+    );
+}
+
+/// Helper to create a response from a non-KV engine, e.g. `database/creds/<role>`.
+fn vault_dynamic_response(
+    data: serde_json::Value,
+    lease_id: &str,
+    lease_duration: u64,
+) -> serde_json::Value {
+    serde_json::json!({
+        "request_id": "test-request-id",
+        "lease_id": lease_id,
+        "renewable": true,
+        "lease_duration": lease_duration,
+        "data": data,
+        "wrap_info": null,
+        "warnings": null,
+        "auth": null
+    })
+}
+
+#[tokio::test]
+async fn test_vault_dynamic_secret_read() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/database/creds/app"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_dynamic_response(
+                serde_json::json!({"username": "v-app-1", "password": "p1"}),
+                "database/creds/app/abc",
+                3600,
+            )),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let secrets_service = SecretsService::new(client);
+
+    let username = secrets_service
+        .get("database/creds/app", "username")
+        .await
+        .unwrap();
+    let password = secrets_service
+        .get("database/creds/app", "password")
+        .await
+        .unwrap();
+    assert_eq!(username, serde_json::json!("v-app-1"));
+    assert_eq!(password, serde_json::json!("p1"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_vault_concurrent_misses_share_one_read() {
+    let mock_server = MockServer::start().await;
+
+    // Slow enough for both lookups to miss the cache
+    Mock::given(method("GET"))
+        .and(path("/v1/database/creds/app"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(vault_dynamic_response(
+                    serde_json::json!({"username": "v-app-1", "password": "p1"}),
+                    "database/creds/app/abc",
+                    3600,
+                ))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let secrets_service = SecretsService::new(client);
+
+    let (username, password) = tokio::join!(
+        secrets_service.get("database/creds/app", "username"),
+        secrets_service.get("database/creds/app", "password"),
+    );
+    assert_eq!(username.unwrap(), serde_json::json!("v-app-1"));
+    assert_eq!(password.unwrap(), serde_json::json!("p1"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_vault_dynamic_secret_rotation_notifies_watchers() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/database/creds/app"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_dynamic_response(
+                serde_json::json!({"username": "v-app-1", "password": "p1"}),
+                "database/creds/app/abc",
+                1,
+            )),
+        )
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/database/creds/app"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(vault_dynamic_response(
+                serde_json::json!({"username": "v-app-2", "password": "p2"}),
+                "database/creds/app/def",
+                3600,
+            )),
+        )
+        .mount(&mock_server)
+        .await;
+
+    // The lease hit its max TTL and can't be extended
+    Mock::given(method("PUT"))
+        .and(path("/v1/sys/leases/renew"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "errors": ["lease is not renewable"]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let settings_path = dir.path().join("settings.json");
+    std::fs::write(
+        &settings_path,
+        r#"[{"key": "DB", "priority": 0, "value": {
+            "username": {"$secret": "database/creds/app:username"},
+            "password": {"$secret": "database/creds/app:password"}
+        }}]"#,
+    )
+    .unwrap();
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let settings = RuntimeSettings::builder()
+        .application("test-app")
+        .mcs_enabled(false)
+        .env_enabled(false)
+        .file_path(settings_path.to_str().unwrap())
+        .vault_client(client)
+        .build()
+        .unwrap();
+    settings.init().await.unwrap();
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_clone = seen.clone();
+    settings.add_watcher(
+        "DB",
+        Box::new(move |_old, new| {
+            seen_clone.lock().unwrap().push(new);
+            Box::pin(async {})
+        }),
+    );
+
+    settings.refresh().await.unwrap();
+    let db = settings.get::<serde_json::Value>("DB").unwrap();
+    assert_eq!(db["password"], "p1");
+
+    tokio::time::sleep(Duration::from_millis(800)).await;
+    settings.refresh().await.unwrap();
+
+    let db = settings.get::<serde_json::Value>("DB").unwrap();
+    assert_eq!(db["username"], "v-app-2");
+    assert_eq!(db["password"], "p2");
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(
+        seen[1],
+        Some(serde_json::json!({"username": "v-app-2", "password": "p2"}))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_vault_secret_template_in_string() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/db"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(
+            serde_json::json!({"user": "app", "password": "s3cr3t", "port": 5432}),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let settings_path = dir.path().join("settings.json");
+    std::fs::write(
+        &settings_path,
+        r#"[{"key": "DSN", "priority": 0, "value":
+            "postgres://${secret:secret/data/db:user}:${secret:secret/data/db:password}@db:${secret:secret/data/db:port}/app?note=$${secret:literal}"
+        }]"#,
+    )
+    .unwrap();
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let settings = RuntimeSettings::builder()
+        .application("test-app")
+        .mcs_enabled(false)
+        .env_enabled(false)
+        .file_path(settings_path.to_str().unwrap())
+        .vault_client(client)
+        .build()
+        .unwrap();
+    settings.init().await.unwrap();
+
+    let dsn = settings.get::<String>("DSN").unwrap();
+    assert_eq!(
+        *dsn,
+        "postgres://app:s3cr3t@db:5432/app?note=${secret:literal}"
+    );
+}
+
+/// Settings file referencing two secret paths, one of them in a template
+fn prefetch_settings_file(dir: &tempfile::TempDir) -> String {
+    let settings_path = dir.path().join("settings.json");
+    std::fs::write(
+        &settings_path,
+        r#"[
+            {"key": "DB_PASSWORD", "priority": 0, "value": {"$secret": "secret/data/db:password"}},
+            {"key": "API_URL", "priority": 0, "value": "https://${secret:secret/data/api:token}@api"}
+        ]"#,
+    )
+    .unwrap();
+    settings_path.to_str().unwrap().to_string()
+}
+
+async fn prefetch_settings(
+    mock_server: &MockServer,
+    path: &str,
+    mode: SecretsPrefetch,
+) -> RuntimeSettings {
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    RuntimeSettings::builder()
+        .application("test-app")
+        .mcs_enabled(false)
+        .env_enabled(false)
+        .file_path(path)
+        .vault_client(client)
+        .prefetch_secrets(mode)
+        .prefetch_concurrency(2)
+        .build()
+        .unwrap()
+}
+
+// Current-thread runtime: prefetched secrets are served from the cache
+#[tokio::test]
+async fn test_vault_prefetch_secrets_at_init() {
+    let mock_server = MockServer::start().await;
+
+    for (secret_path, data) in [
+        (
+            "/v1/secret/data/db",
+            serde_json::json!({"password": "db-pass"}),
+        ),
+        (
+            "/v1/secret/data/api",
+            serde_json::json!({"token": "api-token"}),
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(secret_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(data)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = prefetch_settings_file(&dir);
+    let settings = prefetch_settings(&mock_server, &path, SecretsPrefetch::FailFast).await;
+    settings.init().await.unwrap();
+
+    let password = settings.get::<String>("DB_PASSWORD").unwrap();
+    assert_eq!(*password, "db-pass");
+    let url = settings.get::<String>("API_URL").unwrap();
+    assert_eq!(*url, "https://api-token@api");
+}
+
+#[tokio::test]
+async fn test_vault_prefetch_fail_fast_on_missing_secret() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/db"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(vault_kv2_response(serde_json::json!({"user": "app"}))),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/api"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(
+            serde_json::json!({"token": "api-token"}),
+        )))
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = prefetch_settings_file(&dir);
+    let settings = prefetch_settings(&mock_server, &path, SecretsPrefetch::FailFast).await;
+
+    let result = settings.init().await;
+    assert!(matches!(
+        result,
+        Err(SettingsError::SecretKeyNotFound { path, key }) if path == "secret/data/db" && key == "password"
+    ));
+}
+
+#[tokio::test]
+async fn test_vault_prefetch_warn_on_missing_secret() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/db"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "errors": []
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/api"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(
+            serde_json::json!({"token": "api-token"}),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = prefetch_settings_file(&dir);
+    let settings = prefetch_settings(&mock_server, &path, SecretsPrefetch::Warn).await;
+
+    settings.init().await.unwrap();
+    let url = settings.get::<String>("API_URL").unwrap();
+    assert_eq!(*url, "https://api-token@api");
+}
+
+#[tokio::test]
+async fn test_vault_get_secret_wrapper() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/db"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(
+            serde_json::json!({"password": "db-pass"}),
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/api"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(
+            serde_json::json!({"token": "api-token"}),
+        )))
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = prefetch_settings_file(&dir);
+    let settings = prefetch_settings(&mock_server, &path, SecretsPrefetch::FailFast).await;
+    settings.init().await.unwrap();
+
+    let password = settings.get::<Secret<String>>("DB_PASSWORD").unwrap();
+    assert_eq!(password.expose_secret(), "db-pass");
+    assert_eq!(
+        format!("{:?} {}", password, password),
+        "[REDACTED] [REDACTED]"
+    );
+
+    let explanation = settings.explain("DB_PASSWORD");
+    assert_eq!(explanation.candidates[0].value, "[REDACTED]");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_vault_pinned_kv_version() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/db"))
+        .and(query_param("version", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(
+            serde_json::json!({"password": "old-pass"}),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/db"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(
+            serde_json::json!({"password": "new-pass"}),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let settings_path = dir.path().join("settings.json");
+    std::fs::write(
+        &settings_path,
+        r#"[
+            {"key": "PINNED", "priority": 0, "value": {"$secret": "secret/data/db:password@1"}},
+            {"key": "DSN", "priority": 0, "value": "db://${secret:secret/data/db:password@1}@db"},
+            {"key": "LATEST", "priority": 0, "value": {"$secret": "secret/data/db:password"}}
+        ]"#,
+    )
+    .unwrap();
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let settings = RuntimeSettings::builder()
+        .application("test-app")
+        .mcs_enabled(false)
+        .env_enabled(false)
+        .file_path(settings_path.to_str().unwrap())
+        .vault_client(client)
+        .build()
+        .unwrap();
+    settings.init().await.unwrap();
+
+    // Each version is cached on its own
+    assert_eq!(*settings.get::<String>("PINNED").unwrap(), "old-pass");
+    assert_eq!(*settings.get::<String>("DSN").unwrap(), "db://old-pass@db");
+    assert_eq!(*settings.get::<String>("LATEST").unwrap(), "new-pass");
+
+    // Pinned versions are never refreshed
+    settings.refresh().await.unwrap();
+    assert_eq!(*settings.get::<String>("PINNED").unwrap(), "old-pass");
+}
+
+#[tokio::test]
+async fn test_vault_pinned_version_missing_key_is_not_refetched() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/secret/data/db"))
+        .and(query_param("version", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vault_kv2_response(
+            serde_json::json!({"password": "old-pass"}),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = mock_vault_client(&mock_server.uri(), "test-token").await;
+    let secrets_service = SecretsService::new(client);
+
+    let value = secrets_service
+        .get_at("secret/data/db", "password", Some(1))
+        .await
+        .unwrap();
+    assert_eq!(value, serde_json::json!("old-pass"));
+
+    for _ in 0..2 {
+        let result = secrets_service
+            .get_at("secret/data/db", "user", Some(1))
+            .await;
+        assert!(matches!(
+            result,
+            Err(SettingsError::SecretKeyNotFound { .. })
+        ));
+    }
+}
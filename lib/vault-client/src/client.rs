@@ -2,7 +2,7 @@ use crate::auth::{
     AuthMethod, KubernetesAuth, OidcAuth, StaticTokenAuth, TokenManager, TokenManagerConfig,
 };
 use crate::error::VaultError;
-use crate::models::{KvData, KvMetadata, KvVersion};
+use crate::models::{KvData, KvMetadata, KvVersion, LeaseInfo, SecretData};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -203,7 +203,30 @@ impl VaultClient {
     ///
     /// Example: `kv_read_raw("secret/data/database/creds")` reads from `/v1/secret/data/database/creds`
     pub async fn kv_read_raw(&self, full_path: &str) -> Result<KvData, VaultError> {
-        let url = format!("{}/v1/{}", self.base_url, full_path);
+        self.kv_read_at(full_path, None).await
+    }
+
+    /// Read a specific version of a KV2 secret using full path.
+    ///
+    /// Example: `kv_read_version("secret/data/database/creds", 5)` reads from
+    /// `/v1/secret/data/database/creds?version=5`
+    pub async fn kv_read_version(
+        &self,
+        full_path: &str,
+        version: u64,
+    ) -> Result<KvData, VaultError> {
+        self.kv_read_at(full_path, Some(version)).await
+    }
+
+    async fn kv_read_at(
+        &self,
+        full_path: &str,
+        version: Option<u64>,
+    ) -> Result<KvData, VaultError> {
+        let mut url = format!("{}/v1/{}", self.base_url, full_path);
+        if let Some(version) = version {
+            url.push_str(&format!("?version={}", version));
+        }
         let token = self.token_manager.get_token().await;
 
         let client = reqwest::Client::new();
@@ -237,6 +260,8 @@ impl VaultClient {
         #[derive(serde::Deserialize)]
         struct KvResponse {
             data: KvResponseData,
+            #[serde(flatten)]
+            lease: LeaseInfo,
         }
 
         #[derive(serde::Deserialize)]
@@ -276,9 +301,99 @@ impl VaultClient {
                     .and_then(|s| s.parse().ok()),
                 destroyed: resp.data.metadata.destroyed,
             },
+            lease: resp.lease,
         })
     }
 
+    /// Read a secret from any engine with a plain GET on its full path.
+    ///
+    /// Example: `read("database/creds/my-role")` reads from `/v1/database/creds/my-role`
+    pub async fn read(&self, full_path: &str) -> Result<SecretData, VaultError> {
+        let url = format!("{}/v1/{}", self.base_url, full_path);
+        let token = self.token_manager.get_token().await;
+
+        let client = reqwest::Client::new();
+        let mut request = client.get(&url).header("X-Vault-Token", token);
+
+        if let Some(ref app_name) = self.application_name {
+            request = request.header("User-Agent", app_name);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| VaultError::RequestError(e.to_string()))?;
+
+        if response.status().as_u16() == 404 {
+            return Err(VaultError::SecretNotFound {
+                path: full_path.to_string(),
+            });
+        }
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            return Err(VaultError::ClientError {
+                status,
+                message: body,
+                response_data: None,
+            });
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| VaultError::RequestError(format!("Invalid response: {}", e)))
+    }
+
+    /// Renew a lease via `sys/leases/renew`.
+    ///
+    /// `increment` requests a new TTL; Vault may grant less. Returns the lease
+    /// as renewed.
+    pub async fn renew_lease(
+        &self,
+        lease_id: &str,
+        increment: Option<Duration>,
+    ) -> Result<LeaseInfo, VaultError> {
+        let url = format!("{}/v1/sys/leases/renew", self.base_url);
+        let token = self.token_manager.get_token().await;
+
+        let mut body = serde_json::json!({ "lease_id": lease_id });
+        if let Some(increment) = increment {
+            body["increment"] = increment.as_secs().into();
+        }
+
+        let client = reqwest::Client::new();
+        let mut request = client
+            .put(&url)
+            .header("X-Vault-Token", token)
+            .json(&body);
+
+        if let Some(ref app_name) = self.application_name {
+            request = request.header("User-Agent", app_name);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| VaultError::RequestError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            return Err(VaultError::ClientError {
+                status,
+                message: body,
+                response_data: None,
+            });
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| VaultError::RequestError(format!("Invalid response: {}", e)))
+    }
+
     pub async fn kv_metadata(&self, mount: &str, path: &str) -> Result<KvMetadata, VaultError> {
         let url = format!("{}/v1/{}/metadata/{}", self.base_url, mount, path);
         let token = self.token_manager.get_token().await;
@@ -13,6 +13,10 @@
 //! let client = VaultClient::from_env().await?;
 //! let secret = client.kv_read("secret", "my/path").await?;
 //! println!("{:?}", secret.data);
+//!
+//! // Dynamic secrets from other engines
+//! let creds = client.read("database/creds/my-role").await?;
+//! println!("{:?}", creds.lease);
 //! ```
 
 mod auth;
@@ -22,7 +26,7 @@ mod models;
 
 pub use client::{VaultClient, VaultClientBuilder};
 pub use error::VaultError;
-pub use models::{KvData, KvMetadata, KvVersion};
+pub use models::{KvData, KvMetadata, KvVersion, LeaseInfo, SecretData};
 
 // Re-export for advanced usage
 pub use auth::TokenInfo;
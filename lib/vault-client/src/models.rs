@@ -7,6 +7,30 @@ use std::collections::HashMap;
 pub struct KvData {
     pub data: HashMap<String, serde_json::Value>,
     pub metadata: KvVersion,
+    #[serde(default)]
+    pub lease: LeaseInfo,
+}
+
+/// Lease attached to a secret. Secrets without a lease have an empty
+/// `lease_id` and a zero `lease_duration`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaseInfo {
+    #[serde(default)]
+    pub lease_id: String,
+    /// Lease TTL in seconds
+    #[serde(default)]
+    pub lease_duration: u64,
+    #[serde(default)]
+    pub renewable: bool,
+}
+
+/// Secret read from a non-KV engine, e.g. `database/creds/<role>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretData {
+    #[serde(default)]
+    pub data: HashMap<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub lease: LeaseInfo,
 }
 
 /// Version information for a secret
@@ -46,6 +70,31 @@ mod tests {
         let data: KvData = serde_json::from_str(json).unwrap();
         assert_eq!(data.metadata.version, 1);
         assert_eq!(data.data.get("username").unwrap(), "admin");
+        assert_eq!(data.lease, LeaseInfo::default());
+    }
+
+    #[test]
+    fn test_lease_info_deserialize() {
+        let json = r#"{"lease_id": "database/creds/app/abc", "lease_duration": 3600, "renewable": true, "data": {}}"#;
+        let lease: LeaseInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(lease.lease_id, "database/creds/app/abc");
+        assert_eq!(lease.lease_duration, 3600);
+        assert!(lease.renewable);
+    }
+
+    #[test]
+    fn test_secret_data_deserialize() {
+        let json = r#"{
+            "request_id": "abc",
+            "lease_id": "database/creds/app/xyz",
+            "lease_duration": 3600,
+            "renewable": true,
+            "data": {"username": "v-app-1", "password": "pw"}
+        }"#;
+        let secret: SecretData = serde_json::from_str(json).unwrap();
+        assert_eq!(secret.data.get("username").unwrap(), "v-app-1");
+        assert_eq!(secret.lease.lease_id, "database/creds/app/xyz");
+        assert_eq!(secret.lease.lease_duration, 3600);
     }
 
     #[test]